@@ -155,6 +155,8 @@ fn get_arg_conversion(args: &[ArgType]) -> Vec<TokenStream> {
                     }
                 }
             }
+            // Supplied
+            ArgType::Supplied => quote! {args.get(#idx).map(|x| x.bind(cx))},
             // Option<T>
             ArgType::Option => {
                 let bind = quote! {x.bind(cx)};
@@ -226,6 +228,7 @@ enum ArgType {
     ArgSlice,
     Option,
     OptionRt,
+    Supplied,
     Other,
 }
 
@@ -237,7 +240,7 @@ impl ArgType {
 
     fn is_positional_arg(self) -> bool {
         use ArgType as A;
-        matches!(self, A::Rt(_) | A::Gc(_) | A::Other | A::Option | A::OptionRt)
+        matches!(self, A::Rt(_) | A::Gc(_) | A::Other | A::Option | A::OptionRt | A::Supplied)
     }
 
     fn is_rest_arg(self) -> bool {
@@ -307,7 +310,7 @@ fn check_invariants(args: &[ArgType], sig: &syn::Signature) -> Result<(), Error>
         return Err(Error::new_spanned(sig, "Found duplicate argument slice in signature"));
     }
 
-    let first_opt = args.iter().position(|x| matches!(x, ArgType::Option));
+    let first_opt = args.iter().position(|x| matches!(x, ArgType::Option | ArgType::Supplied));
     let last_required = args.iter().rposition(|x| x.is_required_arg()).unwrap_or_default();
     if let Some(first_optional) = first_opt {
         if last_required > first_optional {
@@ -382,6 +385,7 @@ fn get_arg_type(ty: &syn::Type) -> Result<ArgType, Error> {
                     }
                 }
                 "OptionalFlag" => ArgType::Option,
+                "Supplied" => ArgType::Supplied,
                 _ => get_object_type(path),
             }
         }
@@ -508,6 +512,7 @@ mod test {
         test_args(quote! {x: Option<u8>}, &[ArgType::Option]);
         test_args(quote! {x: Option<()>}, &[ArgType::Option]);
         test_args(quote! {x: OptionalFlag}, &[ArgType::Option]);
+        test_args(quote! {x: Supplied}, &[ArgType::Supplied]);
         test_args(quote! {x: Option<&Rt<Slot<Object>>>}, &[ArgType::OptionRt]);
         test_args(quote! {x: Option<&Rto<Object>>}, &[ArgType::OptionRt]);
         test_args(quote! {x: &[Object]}, &[ArgType::Slice(Gc::Obj)]);
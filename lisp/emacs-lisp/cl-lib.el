@@ -264,13 +264,15 @@ so that they are registered at compile-time as well as run-time."
   "Return t if NUMBER is negative."
   (< number 0))
 
-(defun cl-oddp (integer)
-  "Return t if INTEGER is odd."
-  (eq (logand integer 1) 1))
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun cl-oddp (integer)
+;;   "Return t if INTEGER is odd."
+;;   (eq (logand integer 1) 1))
 
-(defun cl-evenp (integer)
-  "Return t if INTEGER is even."
-  (eq (logand integer 1) 0))
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun cl-evenp (integer)
+;;   "Return t if INTEGER is even."
+;;   (eq (logand integer 1) 0))
 
 (defconst cl-digit-char-table
   (let* ((digits (make-vector 256 nil))
@@ -283,13 +285,14 @@ so that they are registered at compile-time as well as run-time."
     (funcall populate ?a ?z 10)
     digits))
 
-(defun cl-digit-char-p (char &optional radix)
-  "Test if CHAR is a digit in the specified RADIX (default 10).
-If true return the decimal value of digit CHAR in RADIX."
-  (or (<= 2 (or radix 10) 36)
-      (signal 'args-out-of-range (list 'radix radix '(2 36))))
-  (let ((n (aref cl-digit-char-table char)))
-    (and n (< n (or radix 10)) n)))
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun cl-digit-char-p (char &optional radix)
+;;   "Test if CHAR is a digit in the specified RADIX (default 10).
+;; If true return the decimal value of digit CHAR in RADIX."
+;;   (or (<= 2 (or radix 10) 36)
+;;       (signal 'args-out-of-range (list 'radix radix '(2 36))))
+;;   (let ((n (aref cl-digit-char-table char)))
+;;     (and n (< n (or radix 10)) n)))
 
 (defconst cl-most-positive-float nil
   "The largest value that a Lisp float can hold.
@@ -241,23 +241,24 @@ than this function."
      (end (substring string (- (length string) length)))
      (t (substring string 0 length)))))
 
-(defun string-pad (string length &optional padding start)
-  "Pad STRING to LENGTH using PADDING.
-If PADDING is nil, the space character is used.  If not nil, it
-should be a character.
-
-If STRING is longer than the absolute value of LENGTH, no padding
-is done.
-
-If START is nil (or not present), the padding is done to the end
-of the string, and if non-nil, padding is done to the start of
-the string."
-  (unless (natnump length)
-    (signal 'wrong-type-argument (list 'natnump length)))
-  (let ((pad-length (- length (length string))))
-    (cond ((<= pad-length 0) string)
-          (start (concat (make-string pad-length (or padding ?\s)) string))
-          (t (concat string (make-string pad-length (or padding ?\s)))))))
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun string-pad (string length &optional padding start)
+;;   "Pad STRING to LENGTH using PADDING.
+;; If PADDING is nil, the space character is used.  If not nil, it
+;; should be a character.
+;;
+;; If STRING is longer than the absolute value of LENGTH, no padding
+;; is done.
+;;
+;; If START is nil (or not present), the padding is done to the end
+;; of the string, and if non-nil, padding is done to the start of
+;; the string."
+;;   (unless (natnump length)
+;;     (signal 'wrong-type-argument (list 'natnump length)))
+;;   (let ((pad-length (- length (length string))))
+;;     (cond ((<= pad-length 0) string)
+;;           (start (concat (make-string pad-length (or padding ?\s)) string))
+;;           (t (concat string (make-string pad-length (or padding ?\s)))))))
 
 (defun string-chop-newline (string)
   "Remove the final newline (if any) from STRING."
@@ -753,44 +753,45 @@ non-nil."
 	(setcdr last nil)))
   list)
 
-(defun number-sequence (from &optional to inc)
-  "Return a sequence of numbers from FROM to TO (both inclusive) as a list.
-INC is the increment used between numbers in the sequence and defaults to 1.
-So, the Nth element of the list is (+ FROM (* N INC)) where N counts from
-zero.  TO is included only if there is an N for which TO = FROM + N * INC.
-If TO is nil or numerically equal to FROM, return (FROM).
-If INC is positive and TO is less than FROM, or INC is negative
-and TO is larger than FROM, return nil.
-If INC is zero and TO is neither nil nor numerically equal to
-FROM, signal an error.
-
-This function is primarily designed for integer arguments.
-Nevertheless, FROM, TO and INC can be integer or float.  However,
-floating point arithmetic is inexact.  For instance, depending on
-the machine, it may quite well happen that
-\(number-sequence 0.4 0.6 0.2) returns the one element list (0.4),
-whereas (number-sequence 0.4 0.8 0.2) returns a list with three
-elements.  Thus, if some of the arguments are floats and one wants
-to make sure that TO is included, one may have to explicitly write
-TO as (+ FROM (* N INC)) or use a variable whose value was
-computed with this exact expression.  Alternatively, you can,
-of course, also replace TO with a slightly larger value
-\(or a slightly more negative value if INC is negative)."
-  (if (or (not to) (= from to))
-      (list from)
-    (or inc (setq inc 1))
-    (when (zerop inc) (error "The increment can not be zero"))
-    (let (seq (n 0) (next from))
-      (if (> inc 0)
-          (while (<= next to)
-            (setq seq (cons next seq)
-                  n (1+ n)
-                  next (+ from (* n inc))))
-        (while (>= next to)
-          (setq seq (cons next seq)
-                n (1+ n)
-                next (+ from (* n inc)))))
-      (nreverse seq))))
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun number-sequence (from &optional to inc)
+;;   "Return a sequence of numbers from FROM to TO (both inclusive) as a list.
+;; INC is the increment used between numbers in the sequence and defaults to 1.
+;; So, the Nth element of the list is (+ FROM (* N INC)) where N counts from
+;; zero.  TO is included only if there is an N for which TO = FROM + N * INC.
+;; If TO is nil or numerically equal to FROM, return (FROM).
+;; If INC is positive and TO is less than FROM, or INC is negative
+;; and TO is larger than FROM, return nil.
+;; If INC is zero and TO is neither nil nor numerically equal to
+;; FROM, signal an error.
+;;
+;; This function is primarily designed for integer arguments.
+;; Nevertheless, FROM, TO and INC can be integer or float.  However,
+;; floating point arithmetic is inexact.  For instance, depending on
+;; the machine, it may quite well happen that
+;; \(number-sequence 0.4 0.6 0.2) returns the one element list (0.4),
+;; whereas (number-sequence 0.4 0.8 0.2) returns a list with three
+;; elements.  Thus, if some of the arguments are floats and one wants
+;; to make sure that TO is included, one may have to explicitly write
+;; TO as (+ FROM (* N INC)) or use a variable whose value was
+;; computed with this exact expression.  Alternatively, you can,
+;; of course, also replace TO with a slightly larger value
+;; \(or a slightly more negative value if INC is negative)."
+;;   (if (or (not to) (= from to))
+;;       (list from)
+;;     (or inc (setq inc 1))
+;;     (when (zerop inc) (error "The increment can not be zero"))
+;;     (let (seq (n 0) (next from))
+;;       (if (> inc 0)
+;;           (while (<= next to)
+;;             (setq seq (cons next seq)
+;;                   n (1+ n)
+;;                   next (+ from (* n inc))))
+;;         (while (>= next to)
+;;           (setq seq (cons next seq)
+;;                 n (1+ n)
+;;                 next (+ from (* n inc)))))
+;;       (nreverse seq))))
 
 (defun copy-tree (tree &optional vecp)
   "Make a copy of TREE.
@@ -5390,37 +5391,39 @@ The SEPARATOR regexp defaults to \"\\s-+\"."
 
 ;;;; Replacement in strings.
 
-(defun subst-char-in-string (fromchar tochar string &optional inplace)
-  "Replace FROMCHAR with TOCHAR in STRING each time it occurs.
-Unless optional argument INPLACE is non-nil, return a new string."
-  (let ((i (length string))
-	(newstr (if inplace string (copy-sequence string))))
-    (while (> i 0)
-      (setq i (1- i))
-      (if (eq (aref newstr i) fromchar)
-	  (aset newstr i tochar)))
-    newstr))
-
-(defun string-replace (from-string to-string in-string)
-  "Replace FROM-STRING with TO-STRING in IN-STRING each time it occurs."
-  (declare (pure t) (side-effect-free t))
-  (when (equal from-string "")
-    (signal 'wrong-length-argument '(0)))
-  (let ((start 0)
-        (result nil)
-        pos)
-    (while (setq pos (string-search from-string in-string start))
-      (unless (= start pos)
-        (push (substring in-string start pos) result))
-      (push to-string result)
-      (setq start (+ pos (length from-string))))
-    (if (null result)
-        ;; No replacements were done, so just return the original string.
-        in-string
-      ;; Get any remaining bit.
-      (unless (= start (length in-string))
-        (push (substring in-string start) result))
-      (apply #'concat (nreverse result)))))
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun subst-char-in-string (fromchar tochar string &optional inplace)
+;;   "Replace FROMCHAR with TOCHAR in STRING each time it occurs.
+;; Unless optional argument INPLACE is non-nil, return a new string."
+;;   (let ((i (length string))
+;; 	(newstr (if inplace string (copy-sequence string))))
+;;     (while (> i 0)
+;;       (setq i (1- i))
+;;       (if (eq (aref newstr i) fromchar)
+;; 	  (aset newstr i tochar)))
+;;     newstr))
+
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun string-replace (from-string to-string in-string)
+;;   "Replace FROM-STRING with TO-STRING in IN-STRING each time it occurs."
+;;   (declare (pure t) (side-effect-free t))
+;;   (when (equal from-string "")
+;;     (signal 'wrong-length-argument '(0)))
+;;   (let ((start 0)
+;;         (result nil)
+;;         pos)
+;;     (while (setq pos (string-search from-string in-string start))
+;;       (unless (= start pos)
+;;         (push (substring in-string start pos) result))
+;;       (push to-string result)
+;;       (setq start (+ pos (length from-string))))
+;;     (if (null result)
+;;         ;; No replacements were done, so just return the original string.
+;;         in-string
+;;       ;; Get any remaining bit.
+;;       (unless (= start (length in-string))
+;;         (push (substring in-string start) result))
+;;       (apply #'concat (nreverse result)))))
 
 (defun replace-regexp-in-string (regexp rep string &optional
 					fixedcase literal subexp start)
@@ -5489,25 +5492,27 @@ See also `string-equal'."
   (declare (pure t) (side-effect-free t))
   (eq t (compare-strings string1 0 nil string2 0 nil t)))
 
-(defun string-prefix-p (prefix string &optional ignore-case)
-  "Return non-nil if PREFIX is a prefix of STRING.
-If IGNORE-CASE is non-nil, the comparison is done without paying attention
-to case differences."
-  (declare (pure t) (side-effect-free t))
-  (let ((prefix-length (length prefix)))
-    (if (> prefix-length (length string)) nil
-      (eq t (compare-strings prefix 0 prefix-length string
-			     0 prefix-length ignore-case)))))
-
-(defun string-suffix-p (suffix string  &optional ignore-case)
-  "Return non-nil if SUFFIX is a suffix of STRING.
-If IGNORE-CASE is non-nil, the comparison is done without paying
-attention to case differences."
-  (declare (pure t) (side-effect-free t))
-  (let ((start-pos (- (length string) (length suffix))))
-    (and (>= start-pos 0)
-         (eq t (compare-strings suffix nil nil
-                                string start-pos nil ignore-case)))))
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun string-prefix-p (prefix string &optional ignore-case)
+;;   "Return non-nil if PREFIX is a prefix of STRING.
+;; If IGNORE-CASE is non-nil, the comparison is done without paying attention
+;; to case differences."
+;;   (declare (pure t) (side-effect-free t))
+;;   (let ((prefix-length (length prefix)))
+;;     (if (> prefix-length (length string)) nil
+;;       (eq t (compare-strings prefix 0 prefix-length string
+;; 			     0 prefix-length ignore-case)))))
+
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun string-suffix-p (suffix string  &optional ignore-case)
+;;   "Return non-nil if SUFFIX is a suffix of STRING.
+;; If IGNORE-CASE is non-nil, the comparison is done without paying
+;; attention to case differences."
+;;   (declare (pure t) (side-effect-free t))
+;;   (let ((start-pos (- (length string) (length suffix))))
+;;     (and (>= start-pos 0)
+;;          (eq t (compare-strings suffix nil nil
+;;                                 string start-pos nil ignore-case)))))
 
 (defun bidi-string-mark-left-to-right (str)
   "Return a string that can be safely inserted in left-to-right text.
@@ -6880,27 +6885,30 @@ returned list are in the same order as in TREE.
 ;; for discoverability:
 (defalias 'flatten-list #'flatten-tree)
 
-(defun string-trim-left (string &optional regexp)
-  "Trim STRING of leading string matching REGEXP.
-
-REGEXP defaults to \"[ \\t\\n\\r]+\"."
-  (if (string-match (concat "\\`\\(?:" (or regexp "[ \t\n\r]+") "\\)") string)
-      (substring string (match-end 0))
-    string))
-
-(defun string-trim-right (string &optional regexp)
-  "Trim STRING of trailing string matching REGEXP.
-
-REGEXP defaults to  \"[ \\t\\n\\r]+\"."
-  (let ((i (string-match-p (concat "\\(?:" (or regexp "[ \t\n\r]+") "\\)\\'")
-                           string)))
-    (if i (substring string 0 i) string)))
-
-(defun string-trim (string &optional trim-left trim-right)
-  "Trim STRING of leading and trailing strings matching TRIM-LEFT and TRIM-RIGHT.
-
-TRIM-LEFT and TRIM-RIGHT default to \"[ \\t\\n\\r]+\"."
-  (string-trim-left (string-trim-right string trim-right) trim-left))
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun string-trim-left (string &optional regexp)
+;;   "Trim STRING of leading string matching REGEXP.
+;;
+;; REGEXP defaults to \"[ \\t\\n\\r]+\"."
+;;   (if (string-match (concat "\\`\\(?:" (or regexp "[ \t\n\r]+") "\\)") string)
+;;       (substring string (match-end 0))
+;;     string))
+
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun string-trim-right (string &optional regexp)
+;;   "Trim STRING of trailing string matching REGEXP.
+;;
+;; REGEXP defaults to  \"[ \\t\\n\\r]+\"."
+;;   (let ((i (string-match-p (concat "\\(?:" (or regexp "[ \t\n\r]+") "\\)\\'")
+;;                            string)))
+;;     (if i (substring string 0 i) string)))
+
+;; RUNE-BOOTSTRAP - defined in Rust
+;; (defun string-trim (string &optional trim-left trim-right)
+;;   "Trim STRING of leading and trailing strings matching TRIM-LEFT and TRIM-RIGHT.
+;;
+;; TRIM-LEFT and TRIM-RIGHT default to \"[ \\t\\n\\r]+\"."
+;;   (string-trim-left (string-trim-right string trim-right) trim-left))
 
 ;; The initial anchoring is for better performance in searching matches.
 (defconst regexp-unmatchable "\\`a\\`"
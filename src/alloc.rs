@@ -7,6 +7,17 @@ use crate::core::object::{
 use anyhow::{Result, ensure};
 use rune_macros::{defun, elprop};
 
+/// The largest number of elements in a string, vector, or list that can be
+/// created at once. Larger sizes are an error instead of an attempt to
+/// allocate them.
+pub(crate) const MAX_ARRAY_SIZE: usize = i32::MAX as usize;
+
+/// Check that an array of `size` elements is not too large to create.
+pub(crate) fn check_array_size(size: usize) -> Result<()> {
+    ensure!(size <= MAX_ARRAY_SIZE, "Maximum buffer/string size exceeded");
+    Ok(())
+}
+
 #[defun]
 pub(crate) fn list<'ob>(objects: &[Object<'ob>], cx: &'ob Context) -> Object<'ob> {
     let mut head = NIL;
@@ -64,8 +75,15 @@ pub(crate) fn make_byte_code<'ob>(
 
 #[defun]
 #[elprop(u8, _)]
-fn make_vector(length: usize, init: Object) -> Vec<Object> {
-    vec![init; length]
+fn make_vector(length: usize, init: Object) -> Result<Vec<Object>> {
+    check_array_size(length)?;
+    Ok(vec![init; length])
+}
+
+#[defun]
+fn make_list<'ob>(length: usize, init: Object<'ob>, cx: &'ob Context) -> Result<Object<'ob>> {
+    check_array_size(length)?;
+    Ok((0..length).fold(NIL, |list, _| Cons::new(init, list, cx).into()))
 }
 
 #[defun]
@@ -102,6 +120,7 @@ mod test {
     use rune_core::macros::root;
 
     use crate::core::{env::intern, gc::RootSet, object::ObjectType};
+    use crate::interpreter::{assert_lisp, assert_lisp_signal};
 
     use super::*;
 
@@ -124,4 +143,24 @@ mod test {
         assert_eq!(record[1].get(), "slot1");
         assert_eq!(record[2].get(), "slot2");
     }
+
+    #[test]
+    fn test_make_list() {
+        assert_lisp("(make-list 3 'a)", "(a a a)");
+        assert_lisp("(make-list 0 'a)", "nil");
+        assert_lisp("(make-vector 2 nil)", "[nil nil]");
+    }
+
+    #[test]
+    fn test_max_array_size() {
+        let exceeded = "(error \"Maximum buffer/string size exceeded\")";
+        assert_lisp_signal("(make-string (1- (expt 2 55)) ?a)", exceeded);
+        assert_lisp_signal("(make-string (expt 2 40) ?λ)", exceeded);
+        assert_lisp_signal("(make-string (expt 2 40) ?a t)", exceeded);
+        assert_lisp_signal("(make-vector (expt 2 40) nil)", exceeded);
+        assert_lisp_signal("(make-list (1- (expt 2 55)) nil)", exceeded);
+        // the limit for multibyte strings is in bytes
+        let limit = MAX_ARRAY_SIZE / 2 + 1;
+        assert_lisp_signal(&format!("(make-string {limit} ?λ)"), exceeded);
+    }
 }
@@ -1,10 +1,25 @@
 //! Arithmetic operators.
-use crate::core::object::{Gc, IntoObject, Number, NumberType, ObjectType};
+use crate::{
+    alloc::{MAX_ARRAY_SIZE, check_array_size},
+    core::{
+        cons::Cons,
+        env::sym,
+        error::{Type, TypeError},
+        gc::Context,
+        object::{Gc, IntoObject, LispBigInt, Number, NumberType, Object, ObjectType, write_float},
+    },
+    data::LispError,
+    fns::slice_into_list,
+};
+use anyhow::{Result, ensure};
 use float_cmp::ApproxEq;
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
+use num_integer::{Integer, Roots};
+use num_rational::BigRational;
 use num_traits::{FromPrimitive, ToPrimitive, Zero};
+use rune_core::macros::list;
 use rune_macros::defun;
-use std::cmp::PartialEq;
+use std::cmp::{Ordering, PartialEq};
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 
 pub(crate) const MAX_FIXNUM: i64 = i64::MAX >> 8;
@@ -17,6 +32,10 @@ pub(crate) enum NumberValue {
     Int(i64),
     Float(f64),
     Big(BigInt),
+    /// An exact fraction, which is never integral once normalized. Lisp
+    /// numbers are never ratios, so in Lisp one is the cons
+    /// `(NUMERATOR . DENOMINATOR)`, read by [`exact_number`].
+    Ratio(BigRational),
 }
 
 impl Number<'_> {
@@ -37,11 +56,30 @@ impl IntoObject for NumberValue {
             NumberValue::Int(x) => x.into(),
             NumberValue::Float(x) => block.add(x),
             NumberValue::Big(x) => block.add(x),
+            // the same pair that `cl-rational` returns
+            NumberValue::Ratio(x) => {
+                let numer = block.add(NumberValue::Big(x.numer().clone()).normalize());
+                let denom = block.add(NumberValue::Big(x.denom().clone()).normalize());
+                Cons::new(numer, denom, block).into()
+            }
         }
     }
 }
 
 impl NumberValue {
+    /// A total order for sorting that agrees with `<` wherever `<` is
+    /// defined. `-0.0` and `0.0` are equal, and NaN sorts after every other
+    /// number since it is unordered.
+    pub(crate) fn cmp_total(&self, other: &NumberValue) -> Ordering {
+        let is_nan = |x: &NumberValue| matches!(x, NumberValue::Float(x) if x.is_nan());
+        match (is_nan(self), is_nan(other)) {
+            (false, false) => self.partial_cmp(other).unwrap_or(Ordering::Equal),
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+        }
+    }
+
     pub fn coerce_integer(self) -> NumberValue {
         match self {
             NumberValue::Float(x) => {
@@ -56,30 +94,222 @@ impl NumberValue {
                 .filter(|&n| (MIN_FIXNUM..=MAX_FIXNUM).contains(&n))
                 .map(NumberValue::Int)
                 .unwrap_or_else(|| NumberValue::Big(x)),
+            // ratios truncate toward zero like floats
+            NumberValue::Ratio(x) => NumberValue::Big(x.to_integer()).coerce_integer(),
+            other => other,
+        }
+    }
+
+    /// Convert a bignum that fits in a fixnum to an `Int`. All integer results
+    /// should be normalized, so that `Big` only holds values outside the fixnum
+    /// range and a zero bignum can never be observed.
+    fn normalize(self) -> NumberValue {
+        match self {
+            NumberValue::Big(_) => self.coerce_integer(),
+            NumberValue::Ratio(x) if x.is_integer() => NumberValue::Big(x.to_integer()).normalize(),
             other => other,
         }
     }
+
+    /// Whether the number is below zero. Neither `-0.0` nor NaN is negative.
+    pub(crate) fn is_negative(&self) -> bool {
+        match self {
+            NumberValue::Int(x) => *x < 0,
+            NumberValue::Float(x) => *x < 0.0,
+            NumberValue::Big(x) => x.sign() == Sign::Minus,
+            // the denominator of a ratio is always positive
+            NumberValue::Ratio(x) => x.numer().sign() == Sign::Minus,
+        }
+    }
+
+    /// Whether the number is above zero. NaN is not positive.
+    pub(crate) fn is_positive(&self) -> bool {
+        match self {
+            NumberValue::Int(x) => *x > 0,
+            NumberValue::Float(x) => *x > 0.0,
+            NumberValue::Big(x) => x.sign() == Sign::Plus,
+            NumberValue::Ratio(x) => x.numer().sign() == Sign::Plus,
+        }
+    }
+
+    /// Format the number like the printer does, without allocating it in
+    /// the GC heap.
+    pub(crate) fn to_emacs_string(&self) -> String {
+        match self {
+            NumberValue::Int(x) => x.to_string(),
+            NumberValue::Big(x) => x.to_str_radix(10),
+            // like Common Lisp, since Emacs has no syntax for ratios
+            NumberValue::Ratio(x) => format!("{}/{}", x.numer(), x.denom()),
+            NumberValue::Float(x) => {
+                let mut string = String::new();
+                write_float(&mut string, *x).expect("writing to a String can't fail");
+                string
+            }
+        }
+    }
+
+    /// The integer square root of a perfect square. This is `None` for
+    /// floats, negative numbers, and integers whose root is not exact.
+    pub(crate) fn exact_sqrt(&self) -> Option<NumberValue> {
+        match self {
+            NumberValue::Int(x) if *x >= 0 => {
+                let root = Roots::sqrt(x);
+                (root * root == *x).then_some(NumberValue::Int(root))
+            }
+            NumberValue::Big(x) if x.sign() != Sign::Minus => {
+                let root = x.sqrt();
+                (&root * &root == *x).then(|| NumberValue::Big(root).normalize())
+            }
+            _ => None,
+        }
+    }
+
+    fn is_normalized(&self) -> bool {
+        let fixnum = |x: i64| (MIN_FIXNUM..=MAX_FIXNUM).contains(&x);
+        match self {
+            NumberValue::Int(x) => fixnum(*x),
+            NumberValue::Float(_) => true,
+            NumberValue::Big(x) => x.to_i64().is_none_or(|x| !fixnum(x)),
+            NumberValue::Ratio(x) => !x.is_integer(),
+        }
+    }
+
+    /// The exact value of an integer or ratio as a ratio.
+    fn into_ratio(self) -> BigRational {
+        match self {
+            NumberValue::Int(x) => BigRational::from_integer(x.into()),
+            NumberValue::Big(x) => BigRational::from_integer(x),
+            NumberValue::Ratio(x) => x,
+            NumberValue::Float(x) => unreachable!("float {x} has no exact ratio"),
+        }
+    }
+}
+
+/// An integer argument, which can either be a fixnum or a bignum.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum IntOrBig<'ob> {
+    Int(i64),
+    Big(&'ob LispBigInt),
+}
+
+impl From<IntOrBig<'_>> for NumberValue {
+    fn from(value: IntOrBig) -> Self {
+        match value {
+            IntOrBig::Int(x) => NumberValue::Int(x),
+            IntOrBig::Big(x) => NumberValue::Big((**x).clone()),
+        }
+    }
+}
+
+impl From<IntOrBig<'_>> for BigInt {
+    fn from(value: IntOrBig) -> Self {
+        match value {
+            IntOrBig::Int(x) => BigInt::from(x),
+            IntOrBig::Big(x) => (**x).clone(),
+        }
+    }
+}
+
+/// Check that `obj` is an integer, signaling `(wrong-type-argument integerp
+/// obj)` like Emacs if it is not.
+pub(crate) fn require_integer<'ob>(obj: Object<'ob>, cx: &Context) -> Result<IntOrBig<'ob>> {
+    match obj.untag() {
+        ObjectType::Int(x) => Ok(IntOrBig::Int(x)),
+        ObjectType::BigInt(x) => Ok(IntOrBig::Big(x)),
+        _ => Err(LispError::wrong_type(sym::INTEGERP, obj, cx).into()),
+    }
+}
+
+/// Return the value of an integer or marker argument, where a marker stands
+/// for its position.
+pub(crate) fn coerce_to_int(obj: Object, cx: &Context) -> Result<i64> {
+    match obj.untag() {
+        ObjectType::Int(x) => Ok(x),
+        // TODO: use the position once markers are implemented
+        _ => Err(LispError::wrong_type(sym::INTEGER_OR_MARKER_P, obj, cx).into()),
+    }
+}
+
+/// Return the value of `c` as a digit in `radix`. The radix can be up to 36,
+/// using the letters `a` to `z` (in either case) after the decimal digits.
+pub(crate) fn parse_digit(c: char, radix: u32) -> Option<u32> {
+    c.to_digit(radix)
+}
+
+/// Parse an integer with an optional sign in `radix`. The whole string has to
+/// be digits, with no separators like `_`, and values that do not fit in a
+/// fixnum become bignums.
+pub(crate) fn parse_int_in_radix(string: &str, radix: u32) -> Result<NumberValue> {
+    ensure!((2..=36).contains(&radix), "Args out of range: {radix}");
+    let (negative, digits) = match string.as_bytes().first() {
+        Some(b'-') => (true, &string[1..]),
+        Some(b'+') => (false, &string[1..]),
+        _ => (false, string),
+    };
+    let is_digit = |c| parse_digit(c, radix).is_some();
+    ensure!(!digits.is_empty() && digits.chars().all(is_digit), "Not an integer: {string}");
+    let magnitude = digits.chars().try_fold(0_i64, |acc, c| {
+        acc.checked_mul(radix.into())?.checked_add(parse_digit(c, radix)?.into())
+    });
+    match magnitude.map(|x| if negative { -x } else { x }) {
+        Some(x) if (MIN_FIXNUM..=MAX_FIXNUM).contains(&x) => Ok(NumberValue::Int(x)),
+        _ => {
+            let big = BigInt::parse_bytes(digits.as_bytes(), radix).unwrap();
+            Ok(NumberValue::Big(if negative { -big } else { big }))
+        }
+    }
+}
+
+/// Convert a bignum to the nearest float. A bignum too large for a float
+/// becomes infinity with the same sign.
+pub(crate) fn big_to_f64(big: &BigInt) -> f64 {
+    match big.to_f64() {
+        Some(float) => float,
+        None if big.sign() == Sign::Minus => f64::NEG_INFINITY,
+        None => f64::INFINITY,
+    }
+}
+
+/// Convert a ratio to the nearest float, with the same overflow to infinity
+/// as [`big_to_f64`].
+pub(crate) fn ratio_to_f64(ratio: &BigRational) -> f64 {
+    match ratio.to_f64() {
+        Some(float) => float,
+        None if ratio.numer().sign() == Sign::Minus => f64::NEG_INFINITY,
+        None => f64::INFINITY,
+    }
 }
 
 pub(crate) fn arith(
     cur: NumberValue,
     next: NumberValue,
-    int_fn: fn(i64, i64) -> i64,
+    int_fn: fn(i64, i64) -> Option<i64>,
     float_fn: fn(f64, f64) -> f64,
     big_fn: fn(BigInt, BigInt) -> BigInt,
+    ratio_fn: fn(BigRational, BigRational) -> BigRational,
 ) -> NumberValue {
     use NumberValue as N;
-    match (cur, next) {
-        (N::Int(l), N::Int(r)) => N::Int(int_fn(l, r)),
+    let result = match (cur, next) {
+        (N::Int(l), N::Int(r)) => match int_fn(l, r) {
+            Some(x) if (MIN_FIXNUM..=MAX_FIXNUM).contains(&x) => N::Int(x),
+            // overflowed the fixnum range, so redo it as a bignum
+            _ => N::Big(big_fn(l.into(), r.into())).normalize(),
+        },
         (N::Int(l), N::Float(r)) => N::Float(float_fn(l as f64, r)),
         (N::Float(l), N::Int(r)) => N::Float(float_fn(l, r as f64)),
         (N::Float(l), N::Float(r)) => N::Float(float_fn(l, r)),
-        (N::Int(l), N::Big(r)) => N::Big(big_fn(l.into(), r)),
-        (N::Big(l), N::Int(r)) => N::Big(big_fn(l, r.into())),
-        (N::Big(l), N::Big(r)) => N::Big(big_fn(l, r)),
-        (N::Float(l), N::Big(r)) => N::Float(float_fn(l, r.to_f64().unwrap())), // TODO: Should round to nearest float on error
-        (N::Big(l), N::Float(r)) => N::Float(float_fn(l.to_f64().unwrap(), r)), // TODO: Should round to nearest float on error
-    }
+        (N::Int(l), N::Big(r)) => N::Big(big_fn(l.into(), r)).normalize(),
+        (N::Big(l), N::Int(r)) => N::Big(big_fn(l, r.into())).normalize(),
+        (N::Big(l), N::Big(r)) => N::Big(big_fn(l, r)).normalize(),
+        (N::Float(l), N::Big(r)) => N::Float(float_fn(l, big_to_f64(&r))),
+        (N::Big(l), N::Float(r)) => N::Float(float_fn(big_to_f64(&l), r)),
+        (N::Float(l), N::Ratio(r)) => N::Float(float_fn(l, ratio_to_f64(&r))),
+        (N::Ratio(l), N::Float(r)) => N::Float(float_fn(ratio_to_f64(&l), r)),
+        // what is left is exact and involves a ratio
+        (l, r) => N::Ratio(ratio_fn(l.into_ratio(), r.into_ratio())).normalize(),
+    };
+    debug_assert!(result.is_normalized(), "arithmetic result not normalized: {result:?}");
+    result
 }
 
 //////////////////////////
@@ -95,6 +325,7 @@ impl Zero for NumberValue {
             NumberValue::Int(x) => *x == 0,
             NumberValue::Float(x) => *x == 0.0,
             NumberValue::Big(x) => x.is_zero(),
+            NumberValue::Ratio(x) => x.is_zero(),
         }
     }
 }
@@ -103,9 +334,13 @@ impl Neg for NumberValue {
     type Output = Self;
     fn neg(self) -> Self::Output {
         match self {
+            // the negation of the smallest fixnum is a bignum
+            NumberValue::Int(MIN_FIXNUM) => NumberValue::Big(-BigInt::from(MIN_FIXNUM)),
             NumberValue::Int(x) => NumberValue::Int(-x),
             NumberValue::Float(x) => NumberValue::Float(-x),
-            NumberValue::Big(x) => NumberValue::Big(-x),
+            // and the negation of the largest negative bignum is a fixnum
+            NumberValue::Big(x) => NumberValue::Big(-x).normalize(),
+            NumberValue::Ratio(x) => NumberValue::Ratio(-x),
         }
     }
 }
@@ -113,35 +348,35 @@ impl Neg for NumberValue {
 impl Add for NumberValue {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
-        arith(self, rhs, Add::add, Add::add, Add::add)
+        arith(self, rhs, i64::checked_add, Add::add, Add::add, Add::add)
     }
 }
 
 impl Sub for NumberValue {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self::Output {
-        arith(self, rhs, Sub::sub, Sub::sub, Sub::sub)
+        arith(self, rhs, i64::checked_sub, Sub::sub, Sub::sub, Sub::sub)
     }
 }
 
 impl Mul for NumberValue {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self::Output {
-        arith(self, rhs, Mul::mul, Mul::mul, Mul::mul)
+        arith(self, rhs, i64::checked_mul, Mul::mul, Mul::mul, Mul::mul)
     }
 }
 
 impl Div for NumberValue {
     type Output = Self;
     fn div(self, rhs: Self) -> Self::Output {
-        arith(self, rhs, Div::div, Div::div, Div::div)
+        arith(self, rhs, i64::checked_div, Div::div, Div::div, Div::div)
     }
 }
 
 impl Rem for NumberValue {
     type Output = Self;
     fn rem(self, rhs: Self) -> Self::Output {
-        arith(self, rhs, Rem::rem, Rem::rem, Rem::rem)
+        arith(self, rhs, i64::checked_rem, Rem::rem, Rem::rem, Rem::rem)
     }
 }
 
@@ -151,6 +386,8 @@ impl PartialEq<i64> for Number<'_> {
             NumberValue::Int(num) => num == *other,
             NumberValue::Float(num) => num == *other as f64,
             NumberValue::Big(num) => num == BigInt::from(*other),
+            // a normalized ratio is never integral
+            NumberValue::Ratio(_) => false,
         }
     }
 }
@@ -163,6 +400,7 @@ impl PartialEq<f64> for Number<'_> {
             NumberValue::Big(num) => {
                 num.to_f64().is_some_and(|n| n.approx_eq(*other, (f64::EPSILON, 2)))
             } // TODO: Check behavior when conversion fails
+            NumberValue::Ratio(num) => ratio_to_f64(&num).approx_eq(*other, (f64::EPSILON, 2)),
         }
     }
 }
@@ -175,6 +413,7 @@ impl PartialEq<BigInt> for Number<'_> {
                 other.to_f64().is_some_and(|n| n.approx_eq(num, (f64::EPSILON, 2)))
             } // TODO: Check
             NumberValue::Big(num) => num == *other,
+            NumberValue::Ratio(_) => false,
         }
     }
 }
@@ -186,18 +425,26 @@ impl PartialOrd for NumberValue {
                 NumberValue::Int(rhs) => lhs.partial_cmp(rhs),
                 NumberValue::Float(rhs) => (*lhs as f64).partial_cmp(rhs),
                 NumberValue::Big(rhs) => BigInt::from(*lhs).partial_cmp(rhs),
+                NumberValue::Ratio(rhs) => {
+                    BigRational::from_integer((*lhs).into()).partial_cmp(rhs)
+                }
             },
             NumberValue::Float(lhs) => match other {
                 NumberValue::Int(rhs) => lhs.partial_cmp(&(*rhs as f64)),
                 NumberValue::Float(rhs) => lhs.partial_cmp(rhs),
-                NumberValue::Big(rhs) => {
-                    lhs.partial_cmp(&rhs.to_f64().unwrap_or(f64::NAN)) // TODO: Handle conversion failure
-                }
+                NumberValue::Big(rhs) => lhs.partial_cmp(&big_to_f64(rhs)),
+                NumberValue::Ratio(rhs) => lhs.partial_cmp(&ratio_to_f64(rhs)),
             },
             NumberValue::Big(lhs) => match other {
                 NumberValue::Int(rhs) => lhs.partial_cmp(&BigInt::from(*rhs)),
-                NumberValue::Float(rhs) => lhs.to_f64().and_then(|n| n.partial_cmp(rhs)),
+                NumberValue::Float(rhs) => big_to_f64(lhs).partial_cmp(rhs),
                 NumberValue::Big(rhs) => lhs.partial_cmp(rhs),
+                NumberValue::Ratio(rhs) => BigRational::from_integer(lhs.clone()).partial_cmp(rhs),
+            },
+            NumberValue::Ratio(lhs) => match other {
+                NumberValue::Float(rhs) => ratio_to_f64(lhs).partial_cmp(rhs),
+                NumberValue::Ratio(rhs) => lhs.partial_cmp(rhs),
+                rhs => lhs.partial_cmp(&rhs.clone().into_ratio()),
             },
         }
     }
@@ -229,8 +476,156 @@ pub(crate) fn mul(numbers: &[Number]) -> NumberValue {
 }
 
 #[defun(name = "/")]
-pub(crate) fn div(number: Number, divisors: &[Number]) -> NumberValue {
-    divisors.iter().fold(number.val(), |acc, x| acc / x.val())
+pub(crate) fn div(number: Number, divisors: &[Number], cx: &Context) -> Result<NumberValue> {
+    divisors.iter().try_fold(number.val(), |acc, x| {
+        let x = x.val();
+        // only integer division can fail, floats divide to infinity or NaN
+        if x == NumberValue::Int(0) && !matches!(acc, NumberValue::Float(_)) {
+            return Err(LispError::arith_error(cx).into());
+        }
+        Ok(acc / x)
+    })
+}
+
+/// The value of a number, or of a ratio given as a `(NUMERATOR . DENOMINATOR)`
+/// pair of integers.
+pub(crate) fn exact_number(obj: Object, cx: &Context) -> Result<NumberValue> {
+    if let Ok(number) = Number::try_from(obj) {
+        return Ok(number.val());
+    }
+    let integer = |x: Object| match x.untag() {
+        ObjectType::Int(x) => Some(BigInt::from(x)),
+        ObjectType::BigInt(x) => Some((**x).clone()),
+        _ => None,
+    };
+    let parts = match obj.untag() {
+        ObjectType::Cons(cons) => (integer(cons.car()), integer(cons.cdr())),
+        _ => (None, None),
+    };
+    let (Some(numer), Some(denom)) = parts else {
+        return Err(LispError::wrong_type(sym::NUMBERP, obj, cx).into());
+    };
+    if denom.is_zero() {
+        return Err(LispError::arith_error(cx).into());
+    }
+    Ok(NumberValue::Ratio(BigRational::new(numer, denom)).normalize())
+}
+
+/// Divide like `/`, except that dividing integers gives an exact ratio
+/// instead of truncating. Any float argument makes the result a float.
+/// Ratios can be divided again, see [`exact_number`].
+#[defun]
+pub(crate) fn exact_divide(
+    number: Object,
+    divisors: &[Object],
+    cx: &Context,
+) -> Result<NumberValue> {
+    divisors.iter().try_fold(exact_number(number, cx)?, |acc, x| {
+        let x = exact_number(*x, cx)?;
+        if contagion(&[&acc, &x]) {
+            return Ok(acc / x);
+        }
+        if x.is_zero() {
+            return Err(LispError::arith_error(cx).into());
+        }
+        Ok(NumberValue::Ratio(acc.into_ratio() / x.into_ratio()).normalize())
+    })
+}
+
+#[defun]
+pub(crate) fn zerop(number: Number) -> bool {
+    number.val().is_zero()
+}
+
+#[defun]
+fn cl_evenp(integer: Object, cx: &Context) -> Result<bool> {
+    Ok(match require_integer(integer, cx)? {
+        IntOrBig::Int(x) => x % 2 == 0,
+        IntOrBig::Big(x) => x.is_even(),
+    })
+}
+
+#[defun]
+fn cl_oddp(integer: Object, cx: &Context) -> Result<bool> {
+    Ok(!cl_evenp(integer, cx)?)
+}
+
+#[defun]
+fn cl_plusp(number: Number) -> bool {
+    number.val().is_positive()
+}
+
+#[defun]
+fn cl_minusp(number: Number) -> bool {
+    number.val().is_negative()
+}
+
+#[defun]
+fn cl_signum(number: Number) -> i64 {
+    let number = number.val();
+    if number.is_positive() {
+        1
+    } else if number.is_negative() {
+        -1
+    } else {
+        0
+    }
+}
+
+#[defun]
+fn cl_gcd(integers: &[Object], cx: &Context) -> Result<NumberValue> {
+    // gcd is never negative, and zero is the identity
+    let mut result = NumberValue::Int(0);
+    for &integer in integers {
+        let integer = NumberValue::from(require_integer(integer, cx)?);
+        let int_gcd = |x: i64, y: i64| Some(x.gcd(&y));
+        // integers never need the float or ratio cases
+        let big_gcd = |x: BigInt, y: BigInt| x.gcd(&y);
+        result =
+            arith(result, integer, int_gcd, |_, _| unreachable!(), big_gcd, |_, _| unreachable!());
+    }
+    Ok(result)
+}
+
+#[defun]
+fn cl_lcm(integers: &[Object], cx: &Context) -> Result<NumberValue> {
+    let mut result = NumberValue::Int(1);
+    for &integer in integers {
+        let integer = NumberValue::from(require_integer(integer, cx)?);
+        let int_lcm = |x: i64, y: i64| {
+            if x == 0 || y == 0 {
+                return Some(0);
+            }
+            // divide first so only the result itself can overflow
+            (x / x.gcd(&y)).checked_mul(y)?.checked_abs()
+        };
+        // integers never need the float or ratio cases
+        let big_lcm = |x: BigInt, y: BigInt| x.lcm(&y);
+        result =
+            arith(result, integer, int_lcm, |_, _| unreachable!(), big_lcm, |_, _| unreachable!());
+    }
+    Ok(result)
+}
+
+/// The largest integer whose square is no more than `x`.
+#[defun]
+fn cl_isqrt(x: Object, cx: &Context) -> Result<NumberValue> {
+    // like Emacs, anything that is not a natural number is an arith-error
+    let x = match require_integer(x, cx) {
+        Ok(x) => NumberValue::from(x),
+        Err(_) => return Err(LispError::arith_error(cx).into()),
+    };
+    if x.is_negative() {
+        return Err(LispError::arith_error(cx).into());
+    }
+    if let Some(root) = x.exact_sqrt() {
+        return Ok(root);
+    }
+    Ok(match x {
+        NumberValue::Int(x) => NumberValue::Int(Roots::sqrt(&x)),
+        NumberValue::Big(x) => NumberValue::Big(x.sqrt()).normalize(),
+        NumberValue::Float(_) | NumberValue::Ratio(_) => unreachable!(),
+    })
 }
 
 #[defun(name = "1+")]
@@ -249,6 +644,7 @@ pub(crate) fn num_eq(number: Number, numbers: &[Number]) -> bool {
         NumberValue::Int(num) => numbers.iter().all(|&x| x == num),
         NumberValue::Float(num) => numbers.iter().all(|&x| x == num),
         NumberValue::Big(num) => numbers.iter().all(|&x| x == num),
+        NumberValue::Ratio(_) => unreachable!("Lisp numbers are never ratios"),
     }
 }
 
@@ -258,6 +654,7 @@ pub(crate) fn num_ne(number: Number, numbers: &[Number]) -> bool {
         NumberValue::Int(num) => numbers.iter().all(|&x| x != num),
         NumberValue::Float(num) => numbers.iter().all(|&x| x != num),
         NumberValue::Big(num) => numbers.iter().all(|&x| x != num),
+        NumberValue::Ratio(_) => unreachable!("Lisp numbers are never ratios"),
     }
 }
 
@@ -289,52 +686,253 @@ pub(crate) fn greater_than_or_eq(number: Number, numbers: &[Number]) -> bool {
 }
 
 #[defun]
-pub(crate) fn logior(ints_or_markers: &[Gc<i64>]) -> i64 {
-    ints_or_markers.iter().fold(0, |acc, x| acc | x.untag())
+pub(crate) fn logior(ints_or_markers: &[Object], cx: &Context) -> Result<i64> {
+    ints_or_markers.iter().try_fold(0, |acc, x| Ok(acc | coerce_to_int(*x, cx)?))
 }
 
 #[defun]
-fn logand(int_or_markers: &[Gc<i64>]) -> i64 {
-    int_or_markers.iter().fold(-1, |accum, x| accum & x.untag())
+fn logand(int_or_markers: &[Object], cx: &Context) -> Result<i64> {
+    int_or_markers
+        .iter()
+        .try_fold(-1, |accum, x| Ok(accum & coerce_to_int(*x, cx)?))
+}
+
+/// Whether bit number `bit` of `value` is set. Negative values are in two's
+/// complement with the sign bit extended forever, so every bit past their
+/// width is set.
+#[defun]
+fn logbitp(bit: Object, value: Object, cx: &Context) -> Result<bool> {
+    let index = match require_integer(bit, cx)? {
+        IntOrBig::Int(x) if x >= 0 => Some(x as u64),
+        IntOrBig::Big(x) if x.sign() != Sign::Minus => None,
+        _ => return Err(LispError::wrong_type(sym::NATNUMP, bit, cx).into()),
+    };
+    Ok(match (require_integer(value, cx)?, index) {
+        (IntOrBig::Int(x), Some(i)) if i < 64 => (x >> i) & 1 == 1,
+        (IntOrBig::Big(x), Some(i)) => x.bit(i),
+        // any bit past the width of the value is its sign
+        (IntOrBig::Int(x), _) => x < 0,
+        (IntOrBig::Big(x), None) => x.sign() == Sign::Minus,
+    })
 }
 
 #[defun(name = "mod")]
-pub(crate) fn modulo(x: Number, y: Number) -> NumberValue {
-    x.val() % y.val()
+pub(crate) fn modulo(x: Number, y: Number, cx: &Context) -> Result<NumberValue> {
+    let (x, y) = (x.val(), y.val());
+    // a float dividend gives NaN instead of an error
+    if y == NumberValue::Int(0) && !contagion(&[&x]) {
+        return Err(LispError::arith_error(cx).into());
+    }
+    if let (NumberValue::Big(x), NumberValue::Int(y)) = (&x, &y) {
+        return Ok(NumberValue::Int(big_rem_small(x, *y, true)));
+    }
+    let int_mod = |x: i64, y| Some(x.mod_floor(&y));
+    Ok(arith(x, y, int_mod, float_mod, |x, y| x.mod_floor(&y), ratio_mod))
+}
+
+/// `base` raised to `exp` modulo `modulus`, with the sign of the modulus like
+/// `mod`. The power itself is never computed, so this works for exponents far
+/// too large for `expt`.
+#[defun]
+fn mod_expt(base: Object, exp: Object, modulus: Object, cx: &Context) -> Result<NumberValue> {
+    let base = BigInt::from(require_integer(base, cx)?);
+    let exponent = BigInt::from(require_integer(exp, cx)?);
+    let modulus = BigInt::from(require_integer(modulus, cx)?);
+    if exponent.sign() == Sign::Minus {
+        return Err(LispError::wrong_type(sym::NATNUMP, exp, cx).into());
+    }
+    if modulus.is_zero() {
+        return Err(LispError::arith_error(cx).into());
+    }
+    Ok(NumberValue::Big(base.modpow(&exponent, &modulus)).normalize())
+}
+
+/// The remainder of a bignum divided by a fixnum, which always fits in a
+/// fixnum. Dividing by the primitive directly avoids promoting the divisor
+/// to a bignum. With `floor` the result takes the sign of the divisor like
+/// `mod`, otherwise it takes the sign of the dividend like `%`.
+fn big_rem_small(big: &BigInt, small: i64, floor: bool) -> i64 {
+    let rem = (big % small).to_i64().unwrap();
+    if floor && rem != 0 && (rem < 0) != (small < 0) { rem + small } else { rem }
+}
+
+/// Float modulo where the result has the sign of the divisor, so that it is in
+/// `[0, y)` for positive `y` and `(y, 0]` for negative `y`.
+#[expect(clippy::float_cmp)]
+fn float_mod(x: f64, y: f64) -> f64 {
+    let rem = x % y;
+    let rem = if (y < 0.0 && rem > 0.0) || (y > 0.0 && rem < 0.0) { rem + y } else { rem };
+    // adding the divisor to a tiny remainder can round to the divisor itself
+    if rem == y { 0.0 } else { rem }
+}
+
+/// Ratio modulo with the sign of the divisor, like [`float_mod`].
+fn ratio_mod(x: BigRational, y: BigRational) -> BigRational {
+    let quotient = (&x / &y).floor();
+    x - quotient * y
 }
 
 #[defun(name = "%")]
-pub(crate) fn remainder(x: i64, y: i64) -> i64 {
+pub(crate) fn remainder<'ob>(x: Object<'ob>, y: Object<'ob>, cx: &Context) -> Result<NumberValue> {
     // TODO: Handle markers
-    x % y
+    let x = NumberValue::from(require_integer(x, cx)?);
+    let y = NumberValue::from(require_integer(y, cx)?);
+    if y.is_zero() {
+        return Err(LispError::arith_error(cx).into());
+    }
+    if let (NumberValue::Big(x), NumberValue::Int(y)) = (&x, &y) {
+        return Ok(NumberValue::Int(big_rem_small(x, *y, false)));
+    }
+    Ok(x % y)
+}
+
+/// Whether any of `values` is a float. Functions that follow float contagion
+/// return a float when this is true, even if the result came from an
+/// integer argument.
+pub(crate) fn contagion(values: &[&NumberValue]) -> bool {
+    values.iter().any(|x| matches!(x, NumberValue::Float(_)))
 }
 
-#[expect(clippy::trivially_copy_pass_by_ref)]
-fn max_val(x: NumberValue, y: &Number) -> NumberValue {
-    let y = y.val();
-    if x > y { x } else { y }
+/// `value` converted to a float, for results under [`contagion`].
+fn float_value(value: NumberValue) -> NumberValue {
+    match value {
+        NumberValue::Int(x) => NumberValue::Float(x as f64),
+        NumberValue::Big(x) => NumberValue::Float(big_to_f64(&x)),
+        NumberValue::Ratio(x) => NumberValue::Float(ratio_to_f64(&x)),
+        float => float,
+    }
+}
+
+/// The argument that `keep` prefers over all the others, as a float if any
+/// argument is one. Unless it had to be converted, this is the argument
+/// object itself, so the result is `eq` to it like in Emacs. A NaN argument
+/// is always the result, since it doesn't compare against anything.
+fn extremum<'ob>(
+    first: Number<'ob>,
+    rest: &[Number<'ob>],
+    keep: fn(&NumberValue, &NumberValue) -> bool,
+    cx: &'ob Context,
+) -> Object<'ob> {
+    let is_nan = |value: &NumberValue| matches!(value, NumberValue::Float(x) if x.is_nan());
+    let (mut best, mut best_value) = (first, first.val());
+    if is_nan(&best_value) {
+        return best.into();
+    }
+    let mut float = contagion(&[&best_value]);
+    for &number in rest {
+        let value = number.val();
+        if is_nan(&value) {
+            return number.into();
+        }
+        float |= contagion(&[&value]);
+        if !keep(&best_value, &value) {
+            (best, best_value) = (number, value);
+        }
+    }
+    if float && !contagion(&[&best_value]) {
+        cx.add(float_value(best_value))
+    } else {
+        best.into()
+    }
 }
 
-#[expect(clippy::trivially_copy_pass_by_ref)]
-fn min_val(x: NumberValue, y: &Number) -> NumberValue {
-    let y = y.val();
-    if x < y { x } else { y }
+#[defun]
+pub(crate) fn max<'ob>(
+    number_or_marker: Number<'ob>,
+    number_or_markers: &[Number<'ob>],
+    cx: &'ob Context,
+) -> Object<'ob> {
+    extremum(number_or_marker, number_or_markers, |x, y| x > y, cx)
 }
 
 #[defun]
-pub(crate) fn max(number_or_marker: Number, number_or_markers: &[Number]) -> NumberValue {
-    number_or_markers.iter().fold(number_or_marker.val(), max_val)
+pub(crate) fn min<'ob>(
+    number_or_marker: Number<'ob>,
+    number_or_markers: &[Number<'ob>],
+    cx: &'ob Context,
+) -> Object<'ob> {
+    extremum(number_or_marker, number_or_markers, |x, y| x < y, cx)
+}
+
+/// The numbers in a list or vector, for `seq-max` and `seq-min`.
+fn seq_numbers(sequence: Object) -> Result<Vec<Number>> {
+    match sequence.untag() {
+        ObjectType::NIL => Ok(Vec::new()),
+        ObjectType::Cons(cons) => cons.elements().map(|x| Ok(x?.try_into()?)).collect(),
+        ObjectType::Vec(vec) => vec.iter().map(|x| Ok(x.get().try_into()?)).collect(),
+        _ => Err(TypeError::new(Type::Sequence, sequence).into()),
+    }
 }
 
 #[defun]
-pub(crate) fn min(number_or_marker: Number, number_or_markers: &[Number]) -> NumberValue {
-    number_or_markers.iter().fold(number_or_marker.val(), min_val)
+fn seq_max<'ob>(sequence: Object<'ob>, cx: &'ob Context) -> Result<Object<'ob>> {
+    let numbers = seq_numbers(sequence)?;
+    // an empty sequence is the same as calling max with no arguments
+    let Some((first, rest)) = numbers.split_first() else {
+        return Err(LispError::arg_cnt(sym::MAX, 1, 0, cx).into());
+    };
+    Ok(max(*first, rest, cx))
+}
+
+#[defun]
+fn seq_min<'ob>(sequence: Object<'ob>, cx: &'ob Context) -> Result<Object<'ob>> {
+    let numbers = seq_numbers(sequence)?;
+    let Some((first, rest)) = numbers.split_first() else {
+        return Err(LispError::arg_cnt(sym::MIN, 1, 0, cx).into());
+    };
+    Ok(min(*first, rest, cx))
+}
+
+/// The list of numbers from `from` to `to` by `inc`. The nth element is
+/// computed as `from + n * inc` rather than by repeated addition, so float
+/// steps don't accumulate rounding error.
+#[defun]
+fn number_sequence<'ob>(
+    from: Number,
+    to: Option<Number>,
+    inc: Option<Number>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    let start = from.val();
+    let Some(end) =
+        to.map(Number::val).filter(|to| to.partial_cmp(&start) != Some(Ordering::Equal))
+    else {
+        return Ok(list![from; cx]);
+    };
+    let inc = inc.map_or(NumberValue::Int(1), Number::val);
+    ensure!(!inc.is_zero(), "The increment can not be zero");
+    let ascending = inc.is_positive();
+    // the first element is `from` itself, even when `inc` is a float
+    let nth = |n: i64| match n {
+        0 => start.clone(),
+        n => start.clone() + NumberValue::Int(n) * inc.clone(),
+    };
+    let in_range = |n: i64| if ascending { nth(n) <= end } else { nth(n) >= end };
+    // Estimate the count from the number of whole steps, then correct it for
+    // any rounding in the division.
+    let mut count = match (end.clone() - start.clone()) / inc.clone() {
+        NumberValue::Int(steps) => steps.max(-1) + 1,
+        // a NaN end is never reached
+        NumberValue::Float(steps) if steps.is_nan() || steps < 0.0 => 0,
+        NumberValue::Float(steps) if steps < MAX_ARRAY_SIZE as f64 => steps as i64 + 1,
+        _ => i64::MAX,
+    };
+    check_array_size(count as usize)?;
+    while count > 0 && !in_range(count - 1) {
+        count -= 1;
+    }
+    while in_range(count) {
+        count += 1;
+    }
+    let elements: Vec<_> = (0..count).map(|n| cx.add(nth(n))).collect();
+    Ok(slice_into_list(&elements, None, cx))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::core::gc::{Context, RootSet};
+    use crate::interpreter::{assert_lisp, assert_lisp_signal};
 
     #[test]
     fn test_add() {
@@ -399,19 +997,676 @@ mod test {
         let roots = &RootSet::default();
         let cx = &Context::new(roots);
         assert_eq!(
-            max(cx.add_as(1.0), &[cx.add_as(2.1), cx.add_as(1.1), cx.add_as(1.0)]),
-            cx.add_as(2.1).val()
+            max(cx.add_as(1.0), &[cx.add_as(2.1), cx.add_as(1.1), cx.add_as(1.0)], cx),
+            cx.add(2.1)
         );
         assert_eq!(
-            min(cx.add_as(1.1), &[cx.add_as(1.0), cx.add_as(2.1), cx.add_as(1.0)]),
-            cx.add_as(1.0).val()
+            min(cx.add_as(1.1), &[cx.add_as(1.0), cx.add_as(2.1), cx.add_as(1.0)], cx),
+            cx.add(1.0)
         );
     }
 
+    #[test]
+    fn test_max_min_identity() {
+        assert_lisp("(let ((x 1.5)) (eq x (max x)))", "t");
+        assert_lisp("(let ((x 1.5)) (eq x (min x 2.5)))", "t");
+        assert_lisp("(let ((x 2.5)) (eq x (max 1 x 2)))", "t");
+        assert_lisp("(let ((x (expt 2 70))) (eq x (max 1 x)))", "t");
+        assert_lisp("(let ((x [0.5 2.5])) (eq (aref x 1) (seq-max x)))", "t");
+        // an integer that wins against a float has to be converted
+        assert_lisp("(max 3 2.5)", "3.0");
+    }
+
+    #[test]
+    fn test_max_min_nan() {
+        assert_lisp("(let ((x (/ 0.0 0.0))) (eq x (max 1.0 x 5)))", "t");
+        assert_lisp("(let ((x (/ 0.0 0.0))) (eq x (min x 1.0 5)))", "t");
+        assert_lisp("(let ((x (/ 0.0 0.0))) (eq x (max 1 2 x)))", "t");
+        assert_lisp("(let ((x (/ 0.0 0.0))) (eq x (seq-min (list 1 x 5))))", "t");
+    }
+
+    #[test]
+    fn test_normalize() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        let big: Number = cx.add(mul(&[MAX_FIXNUM.into(), 4.into()])).try_into().unwrap();
+        assert!(matches!(big.untag(), NumberType::Big(_)));
+        assert_eq!(sub(Some(big), &[big]), NumberValue::Int(0));
+        assert_eq!(
+            add(&[MAX_FIXNUM.into(), 1.into()]),
+            NumberValue::Big(BigInt::from(MAX_FIXNUM) + 1)
+        );
+        assert_eq!(sub(Some(MIN_FIXNUM.into()), &[]), NumberValue::Big(-BigInt::from(MIN_FIXNUM)));
+        let small: Number = cx.add(sub(Some(MIN_FIXNUM.into()), &[1.into()])).try_into().unwrap();
+        assert_eq!(add(&[small, 1.into()]), NumberValue::Int(MIN_FIXNUM));
+    }
+
+    #[test]
+    fn test_zerop() {
+        assert!(zerop(0.into()));
+        assert!(!zerop(1.into()));
+        assert_lisp("(zerop 0.0)", "t");
+        assert_lisp("(zerop (- (expt 2 70) (expt 2 70)))", "t");
+        assert_lisp("(integerp (- (expt 2 70) (expt 2 70)))", "t");
+        assert_lisp("(eq (- (expt 2 70) (expt 2 70)) 0)", "t");
+        assert_lisp("(zerop (expt 2 70))", "nil");
+    }
+
+    #[test]
+    fn test_sign_predicates() {
+        use NumberValue::{Big, Float, Int};
+        assert!(Int(-1).is_negative());
+        assert!(!Int(0).is_negative() && !Int(0).is_positive());
+        assert!(Int(3).is_positive());
+        assert!(Float(2.5).is_positive() && !Float(2.5).is_negative());
+        assert!(Float(-2.5).is_negative());
+        assert!(!Float(-0.0).is_negative() && !Float(-0.0).is_positive());
+        assert!(!Float(f64::NAN).is_negative() && !Float(f64::NAN).is_positive());
+        assert!(!Float(-f64::NAN).is_negative());
+        assert!(Float(f64::NEG_INFINITY).is_negative());
+        let big = BigInt::from(2).pow(70);
+        assert!(Big(-big.clone()).is_negative() && !Big(-big.clone()).is_positive());
+        assert!(Big(big).is_positive());
+
+        assert_lisp("(cl-plusp 2)", "t");
+        assert_lisp("(cl-plusp -0.0)", "nil");
+        assert_lisp("(cl-minusp -0.0)", "nil");
+        assert_lisp("(cl-minusp (- (expt 2 70)))", "t");
+        assert_lisp("(list (cl-signum -2.5) (cl-signum 0) (cl-signum (expt 2 70)))", "(-1 0 1)");
+        assert_lisp("(cl-signum (/ 0.0 0.0))", "0");
+    }
+
+    #[test]
+    fn test_evenp_oddp() {
+        assert_lisp("(cl-evenp 4)", "t");
+        assert_lisp("(cl-evenp -3)", "nil");
+        assert_lisp("(cl-oddp -3)", "t");
+        assert_lisp("(cl-oddp 0)", "nil");
+        assert_lisp("(cl-evenp (expt 2 70))", "t");
+        assert_lisp("(cl-oddp (1+ (expt 2 70)))", "t");
+        assert_lisp_signal("(cl-evenp 1.5)", "(wrong-type-argument integerp 1.5)");
+        assert_lisp_signal("(cl-oddp \"a\")", "(wrong-type-argument integerp \"a\")");
+    }
+
+    #[test]
+    fn test_parse_int_in_radix() {
+        let parse = |s, radix| parse_int_in_radix(s, radix).unwrap();
+        let big =
+            |s: &str, radix| NumberValue::Big(BigInt::parse_bytes(s.as_bytes(), radix).unwrap());
+        assert_eq!(parse("1011", 2), NumberValue::Int(11));
+        assert_eq!(parse("-1011", 2), NumberValue::Int(-11));
+        assert_eq!(parse("777", 8), NumberValue::Int(511));
+        assert_eq!(parse("+ff", 16), NumberValue::Int(255));
+        assert_eq!(parse("DeadBeef", 16), NumberValue::Int(0xdead_beef));
+        assert_eq!(parse("zz", 36), NumberValue::Int(1295));
+        assert_eq!(parse("007", 10), NumberValue::Int(7));
+        // out of the fixnum range
+        let max = MAX_FIXNUM + 1;
+        assert_eq!(parse(&format!("{max:x}"), 16), NumberValue::Big(max.into()));
+        assert_eq!(parse(&format!("-{max:o}"), 8), NumberValue::Int(-max));
+        assert_eq!(parse(&"1".repeat(100), 2), big(&"1".repeat(100), 2));
+        assert_eq!(parse("-zzzzzzzzzzzzzzzzzz", 36), big("-zzzzzzzzzzzzzzzzzz", 36));
+        // invalid
+        assert!(parse_int_in_radix("", 10).is_err());
+        assert!(parse_int_in_radix("-", 10).is_err());
+        assert!(parse_int_in_radix("12", 2).is_err());
+        assert!(parse_int_in_radix("1_000", 10).is_err());
+        assert!(parse_int_in_radix("g", 16).is_err());
+        assert!(parse_int_in_radix("1", 37).is_err());
+    }
+
+    #[test]
+    fn test_ratio_arithmetic() {
+        use NumberValue as N;
+        let ratio = |n: i64, d: i64| N::Ratio(BigRational::new(n.into(), d.into()));
+        assert_eq!(ratio(1, 3) + ratio(2, 3), N::Int(1));
+        assert_eq!(ratio(1, 3) * N::Int(3), N::Int(1));
+        assert_eq!(ratio(1, 2) - N::Int(1), ratio(-1, 2));
+        assert_eq!(N::Int(1) / ratio(2, 3), ratio(3, 2));
+        assert_eq!(-ratio(1, 3), ratio(-1, 3));
+        assert_eq!(N::Big(BigInt::from(1) << 70) * ratio(1, 2), N::Big(BigInt::from(1) << 69));
+        assert_eq!(ratio(1, 4) + N::Float(0.5), N::Float(0.75));
+        assert!(ratio(1, 3) < N::Float(0.34));
+        assert!(ratio(1, 3) > N::Int(0));
+        assert!(ratio(-7, 2) < N::Int(-3));
+        assert!(ratio(1, 3).is_positive());
+        assert!(ratio(-1, 3).is_negative());
+        assert_eq!(ratio(7, 2).coerce_integer(), N::Int(3));
+    }
+
+    #[test]
+    fn test_ratio_to_float() {
+        let ratio = |n: BigInt, d: i64| BigRational::new(n, d.into());
+        assert_eq!(ratio_to_f64(&ratio(1.into(), 4)), 0.25);
+        assert_eq!(ratio_to_f64(&ratio((-3).into(), 2)), -1.5);
+        let huge = BigInt::from(1) << 2000;
+        assert_eq!(ratio_to_f64(&ratio(huge.clone(), 3)), f64::INFINITY);
+        assert_eq!(ratio_to_f64(&ratio(-huge, 3)), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_exact_divide() {
+        assert_lisp("(exact-divide 1 3)", "(1 . 3)");
+        assert_lisp("(exact-divide 6 4)", "(3 . 2)");
+        assert_lisp("(exact-divide 1 -3)", "(-1 . 3)");
+        assert_lisp("(exact-divide 6 3)", "2");
+        assert_lisp("(exact-divide 1 3 4)", "(1 . 12)");
+        assert_lisp("(exact-divide (expt 2 70) 3)", "(1180591620717411303424 . 3)");
+        // a ratio can be used again
+        assert_lisp("(exact-divide (exact-divide 1 3) 2)", "(1 . 6)");
+        assert_lisp("(exact-divide (exact-divide 1 3) (exact-divide 2 3))", "(1 . 2)");
+        assert_lisp("(exact-divide (exact-divide 2 3) (exact-divide 1 3))", "2");
+        assert_lisp("(exact-divide '(6 . 4))", "(3 . 2)");
+        assert_lisp("(exact-divide (exact-divide 1 4) 0.5)", "0.5");
+        assert_lisp("(float (exact-divide 1 4))", "0.25");
+        assert_lisp("(number-to-string (exact-divide -1 3))", "\"-1/3\"");
+        assert_lisp("(exact-divide (cl-rational 0.75) 3)", "(1 . 4)");
+        assert_lisp("(cl-rational (exact-divide 1 3))", "(1 . 3)");
+        assert_lisp_signal("(exact-divide '(1 . a))", "(wrong-type-argument numberp (1 . a))");
+        assert_lisp_signal("(exact-divide '(1 . 0))", "(arith-error)");
+        assert_lisp("(exact-divide (expt 2 70) (expt 2 69))", "2");
+        assert_lisp("(exact-divide 1 2.0)", "0.5");
+        assert_lisp("(exact-divide 1 3 2.0)", "0.16666666666666666");
+        assert_lisp_signal("(exact-divide 1 0)", "(arith-error)");
+        // plain `/` still truncates
+        assert_lisp("(/ 1 3)", "0");
+    }
+
+    #[test]
+    fn test_contagion() {
+        use NumberValue as N;
+        assert!(!contagion(&[]));
+        assert!(!contagion(&[&N::Int(1), &N::Big(BigInt::from(1) << 70)]));
+        assert!(contagion(&[&N::Int(1), &N::Float(2.0)]));
+        assert_lisp("(max 1 2.0)", "2.0");
+        assert_lisp("(max 3 2.0)", "3.0");
+        assert_lisp("(max 1 2 3)", "3");
+        assert_lisp("(min 1 2.0)", "1.0");
+        assert_lisp("(min 2.5 (expt 2 70))", "2.5");
+        assert_lisp("(max 2.5 (expt 2 70))", "1180591620717411303424.0");
+        assert_lisp("(mod 7 2.0)", "1.0");
+        assert_lisp("(mod 7.5 2)", "1.5");
+        assert_lisp("(mod 7 2)", "1");
+    }
+
+    #[test]
+    fn test_mod() {
+        assert_lisp("(mod 7 2)", "1");
+        assert_lisp("(mod -7 2)", "1");
+        assert_lisp("(mod 7 -2)", "-1");
+        assert_lisp("(mod -7 -2)", "-1");
+        assert_lisp("(mod (expt 2 70) 3)", "1");
+        assert_lisp("(mod (- (expt 2 70)) 3)", "2");
+    }
+
+    #[test]
+    fn test_mod_big_float() {
+        // the bignum is converted to a float first, and the result has the
+        // sign of the divisor
+        assert_lisp("(mod (expt 2 70) 3.5)", "2.0");
+        assert_lisp("(mod (- (expt 2 70)) 3.5)", "1.5");
+        assert_lisp("(mod (expt 2 70) -3.5)", "-1.5");
+        assert_lisp("(floatp (mod (expt 2 70) 4.0))", "t");
+        assert_lisp("(mod 5.5 (expt 2 70))", "5.5");
+        assert_lisp("(= (mod -5.5 (expt 2 70)) (- (float (expt 2 70)) 5.5))", "t");
+        // a bignum too large for a float is infinite, which has no remainder
+        assert_lisp("(isnan (mod (expt 10 400) 3.0))", "t");
+        assert_lisp("(isnan (mod (- (expt 10 400)) 3.0))", "t");
+        assert_lisp("(mod 2.5 (expt 10 400))", "2.5");
+        assert_lisp("(= (+ (expt 10 400) 1.0) (/ 1.0 0.0))", "t");
+        assert_lisp("(= (- 1.0 (expt 10 400)) (/ -1.0 0.0))", "t");
+    }
+
+    #[test]
+    fn test_big_to_f64() {
+        assert_eq!(big_to_f64(&BigInt::from(2).pow(70)), 2f64.powi(70));
+        assert_eq!(big_to_f64(&BigInt::from(10).pow(400)), f64::INFINITY);
+        assert_eq!(big_to_f64(&-BigInt::from(10).pow(400)), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_big_rem_small() {
+        let bigs =
+            [BigInt::from(2).pow(70), -BigInt::from(3).pow(50), BigInt::from(10).pow(30) + 7];
+        let smalls = [1, -1, 2, 7, -7, 10, 1 << 40, MAX_FIXNUM, MIN_FIXNUM];
+        for big in &bigs {
+            for &small in &smalls {
+                let divisor = BigInt::from(small);
+                let rem = big % &divisor;
+                assert_eq!(BigInt::from(big_rem_small(big, small, false)), rem);
+                let modulo = big.mod_floor(&divisor);
+                assert_eq!(BigInt::from(big_rem_small(big, small, true)), modulo);
+            }
+        }
+        assert_lisp("(% (- (expt 2 70)) 7)", "-2");
+        assert_lisp("(mod (- (expt 2 70)) 7)", "5");
+        assert_lisp("(mod (expt 2 70) -7)", "-5");
+        assert_lisp("(cl-typep-number (mod (expt 2 70) (1- (expt 2 55))) 'fixnum)", "t");
+    }
+
+    #[test]
+    fn test_big_rem_small_digits() {
+        // Extracting decimal digits is the typical big-by-small case, so run
+        // it over a few thousand digits.
+        let big = BigInt::from(7).pow(5000);
+        let expected: i64 = big.to_string().bytes().map(|b| i64::from(b - b'0')).sum();
+        let mut n = big;
+        let mut sum = 0;
+        let ten = BigInt::from(10);
+        while !n.is_zero() {
+            sum += big_rem_small(&n, 10, true);
+            n /= &ten;
+        }
+        assert_eq!(sum, expected);
+        assert_lisp(
+            "(let ((n (expt 7 500)) (sum 0))
+               (while (> n 0)
+                 (setq sum (+ sum (% n 10)) n (/ n 10)))
+               sum)",
+            &BigInt::from(7)
+                .pow(500)
+                .to_string()
+                .bytes()
+                .map(|b| i64::from(b - b'0'))
+                .sum::<i64>()
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn test_seq_max_min() {
+        assert_lisp("(seq-max '(3 1 4 1 5))", "5");
+        assert_lisp("(seq-min '(3 1 4 1 5))", "1");
+        assert_lisp("(seq-max [2 7.5 -1])", "7.5");
+        assert_lisp("(seq-min [2 7.5 -1])", "-1.0");
+        assert_lisp("(seq-max (list 1 (expt 2 70)))", "1180591620717411303424");
+        assert_lisp("(seq-min '(4))", "4");
+        assert_lisp(
+            "(condition-case err (seq-max nil) (error (car err)))",
+            "wrong-number-of-arguments",
+        );
+        assert_lisp(
+            "(condition-case err (seq-min []) (error (car err)))",
+            "wrong-number-of-arguments",
+        );
+        assert_lisp("(condition-case nil (seq-max '(1 a)) (error 'type))", "type");
+    }
+
+    #[test]
+    fn test_gcd() {
+        assert_lisp("(cl-gcd)", "0");
+        assert_lisp("(cl-gcd 5)", "5");
+        assert_lisp("(cl-gcd -5)", "5");
+        assert_lisp("(cl-gcd 0 0)", "0");
+        assert_lisp("(cl-gcd 12 8)", "4");
+        assert_lisp("(cl-gcd -12 8)", "4");
+        assert_lisp("(cl-gcd 12 -8)", "4");
+        assert_lisp("(cl-gcd 12 18 -8)", "2");
+        assert_lisp("(cl-gcd 0 -7)", "7");
+        assert_lisp("(cl-gcd (expt 2 70) (expt 6 30))", "1073741824");
+        assert_lisp("(cl-gcd (- (expt 2 55)) 0)", "36028797018963968");
+        assert_lisp("(condition-case err (cl-gcd 4 1.5) (error (car err)))", "wrong-type-argument");
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_lisp("(cl-lcm)", "1");
+        assert_lisp("(cl-lcm -5)", "5");
+        assert_lisp("(cl-lcm 4 6)", "12");
+        assert_lisp("(cl-lcm -4 6)", "12");
+        assert_lisp("(cl-lcm 4 6 10)", "60");
+        assert_lisp("(cl-lcm 4 0 6)", "0");
+        assert_lisp("(cl-lcm 0 0)", "0");
+        // the product of these doesn't fit in a fixnum
+        assert_lisp("(cl-lcm 4294967311 4294967357)", "18446744400127067027");
+        assert_lisp("(cl-typep-number (cl-lcm 4294967311 4294967357) 'bignum)", "t");
+        assert_lisp("(cl-lcm (expt 2 70) 3)", "3541774862152233910272");
+    }
+
+    #[test]
+    fn test_arith_error() {
+        for form in [
+            "(/ 1 0)",
+            "(/ 6 2 0)",
+            "(/ (expt 2 70) 0)",
+            "(% 1 0)",
+            "(% (expt 2 70) 0)",
+            "(mod 1 0)",
+            "(mod (expt 2 70) 0)",
+            "(floor 1 0)",
+            "(ceiling 1 0)",
+            "(round 1 0)",
+            "(truncate 1 0)",
+        ] {
+            assert_lisp_signal(form, "(arith-error)");
+        }
+        // floats don't signal
+        assert_lisp("(= (/ 1.0 0) (/ 1.0 0.0))", "t");
+        assert_lisp("(isnan (mod 1 0.0))", "t");
+    }
+
+    #[test]
+    fn test_remainder() {
+        assert_lisp("(% 7 2)", "1");
+        assert_lisp("(% -7 2)", "-1");
+        assert_lisp("(% 7 -2)", "1");
+        assert_lisp("(% (expt 2 70) 7)", "2");
+        assert_lisp("(cl-typep-number (% (expt 2 70) 7) 'fixnum)", "t");
+        assert_lisp("(% (expt 2 70) (expt 2 69))", "0");
+        assert_lisp("(cl-typep-number (% (* 3 (expt 2 70)) (expt 2 71)) 'bignum)", "t");
+        assert_lisp("(cl-typep-number (mod (- (expt 2 70)) 7) 'fixnum)", "t");
+        assert_lisp("(condition-case err (% 1.0 2) (error (car err)))", "wrong-type-argument");
+    }
+
+    #[test]
+    fn test_float_mod() {
+        assert_lisp("(mod 5.0 1.5)", "0.5");
+        assert_lisp("(mod -5.0 1.5)", "1.0");
+        assert_lisp("(mod 5.0 -1.5)", "-1.0");
+        assert_lisp("(mod -5.0 -1.5)", "-0.5");
+        assert_lisp("(mod 5 1.5)", "0.5");
+        assert_lisp("(mod 4.5 1.5)", "0.0");
+        // tiny remainders with the wrong sign would round to the divisor
+        assert_lisp("(= (mod -1e-20 1.0) 0)", "t");
+        assert_lisp("(= (mod 1e-20 -1.0) 0)", "t");
+        assert_lisp("(< (mod -1e-10 1.0) 1.0)", "t");
+        assert_lisp("(isnan (mod 1.0 0.0))", "t");
+    }
+
+    #[test]
+    fn test_to_emacs_string() {
+        assert_eq!(NumberValue::Int(0).to_emacs_string(), "0");
+        assert_eq!(NumberValue::Int(-42).to_emacs_string(), "-42");
+        assert_eq!(NumberValue::Int(MAX_FIXNUM).to_emacs_string(), "36028797018963967");
+        assert_eq!(NumberValue::Float(1.5).to_emacs_string(), "1.5");
+        assert_eq!(NumberValue::Float(3.0).to_emacs_string(), "3.0");
+        assert_eq!(NumberValue::Float(-0.0).to_emacs_string(), "-0.0");
+        assert_eq!(NumberValue::Float(-2.25).to_emacs_string(), "-2.25");
+        let big = BigInt::from(2).pow(70);
+        assert_eq!(NumberValue::Big(big.clone()).to_emacs_string(), "1180591620717411303424");
+        assert_eq!(NumberValue::Big(-big).to_emacs_string(), "-1180591620717411303424");
+    }
+
+    #[test]
+    fn test_exact_sqrt() {
+        let sqrt = |x: NumberValue| x.exact_sqrt();
+        assert_eq!(sqrt(NumberValue::Int(0)), Some(NumberValue::Int(0)));
+        assert_eq!(sqrt(NumberValue::Int(1)), Some(NumberValue::Int(1)));
+        assert_eq!(sqrt(NumberValue::Int(144)), Some(NumberValue::Int(12)));
+        assert_eq!(sqrt(NumberValue::Int(MAX_FIXNUM)), None);
+        assert_eq!(sqrt(NumberValue::Int(2)), None);
+        assert_eq!(sqrt(NumberValue::Int(143)), None);
+        assert_eq!(sqrt(NumberValue::Int(-4)), None);
+        assert_eq!(sqrt(NumberValue::Float(4.0)), None);
+        let big = BigInt::from(1_u64 << 40);
+        assert_eq!(sqrt(NumberValue::Big(&big * &big)), Some(NumberValue::Big(big.clone())));
+        // roots of bignums that fit in a fixnum are normalized
+        let square = BigInt::from(1_i64 << 30).pow(2) * 4;
+        assert_eq!(sqrt(NumberValue::Big(square)), Some(NumberValue::Int(1 << 31)));
+        assert_eq!(sqrt(NumberValue::Big(&big * &big + 1)), None);
+        assert_eq!(sqrt(NumberValue::Big(-(&big * &big))), None);
+    }
+
+    #[test]
+    fn test_cl_isqrt() {
+        assert_lisp("(cl-isqrt 0)", "0");
+        assert_lisp("(cl-isqrt 16)", "4");
+        assert_lisp("(cl-isqrt 17)", "4");
+        assert_lisp("(cl-isqrt 24)", "4");
+        assert_lisp("(cl-isqrt (expt 10 40))", "100000000000000000000");
+        assert_lisp("(cl-isqrt (1+ (expt 10 40)))", "100000000000000000000");
+        assert_lisp("(cl-isqrt (expt 2 70))", "34359738368");
+        assert_lisp_signal("(cl-isqrt -1)", "(arith-error)");
+        assert_lisp_signal("(cl-isqrt 4.0)", "(arith-error)");
+    }
+
+    #[test]
+    fn test_mod_expt() {
+        assert_lisp("(mod-expt 3 1000 7)", "4");
+        assert_lisp("(mod-expt 2 (expt 10 30) 1000000007)", "312267046");
+        assert_lisp("(mod-expt 3 (expt 2 100) (1+ (expt 2 70)))", "321237458223885966321");
+        assert_lisp("(mod-expt 2 100 (expt 2 70))", "0");
+        assert_lisp("(mod-expt 5 0 7)", "1");
+        assert_lisp("(mod-expt 5 0 1)", "0");
+        // like `mod', the result has the sign of the modulus
+        assert_lisp("(mod-expt -2 3 5)", "2");
+        assert_lisp("(mod-expt 2 3 -5)", "-2");
+        assert_lisp("(cl-typep-number (mod-expt 3 (expt 2 100) 101) 'fixnum)", "t");
+        assert_lisp_signal("(mod-expt 2 -1 7)", "(wrong-type-argument natnump -1)");
+        assert_lisp_signal("(mod-expt 2 3 0)", "(arith-error)");
+        assert_lisp_signal("(mod-expt 2.0 3 5)", "(wrong-type-argument integerp 2.0)");
+    }
+
     #[test]
     fn test_other() {
         let roots = &RootSet::default();
         let cx = &Context::new(roots);
-        assert_eq!(logand(&[258.into_obj(cx), 255.into_obj(cx)]), 2);
+        assert_eq!(logand(&[258.into_obj(cx), 255.into_obj(cx)], cx).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_logand_logior() {
+        assert_lisp("(logand)", "-1");
+        assert_lisp("(logand 12 10)", "8");
+        assert_lisp("(logand -1 6 3)", "2");
+        assert_lisp("(logior)", "0");
+        assert_lisp("(logior 12 10 1)", "15");
+        assert_lisp("(logior -8 3)", "-5");
+        assert_lisp_signal("(logior 1 'a)", "(wrong-type-argument integer-or-marker-p a)");
+        assert_lisp("(integer-or-marker-p 1)", "t");
+        assert_lisp("(integer-or-marker-p 1.0)", "nil");
+    }
+
+    #[test]
+    fn test_logbitp() {
+        assert_lisp("(logbitp 0 5)", "t");
+        assert_lisp("(logbitp 1 5)", "nil");
+        assert_lisp("(logbitp 2 5)", "t");
+        assert_lisp("(logbitp 100 (expt 2 100))", "t");
+        assert_lisp("(logbitp 99 (expt 2 100))", "nil");
+        assert_lisp("(logbitp 101 (expt 2 100))", "nil");
+        // negative numbers have their sign bit extended forever
+        assert_lisp("(logbitp 0 -2)", "nil");
+        assert_lisp("(logbitp 1 -2)", "t");
+        assert_lisp("(logbitp 63 -1)", "t");
+        assert_lisp("(logbitp 1000 -1)", "t");
+        assert_lisp("(logbitp 1000 1)", "nil");
+        assert_lisp("(logbitp (expt 2 70) -1)", "t");
+        assert_lisp("(logbitp (expt 2 70) (expt 2 100))", "nil");
+        // -2^100 is a 1 followed by 100 zeros, so bit 100 and above are set
+        assert_lisp("(logbitp 99 (- (expt 2 100)))", "nil");
+        assert_lisp("(logbitp 100 (- (expt 2 100)))", "t");
+        assert_lisp("(logbitp 200 (- (expt 2 100)))", "t");
+        assert_lisp("(logbitp 0 (- 1 (expt 2 100)))", "t");
+        assert_lisp_signal("(logbitp -1 1)", "(wrong-type-argument natnump -1)");
+        assert_lisp_signal("(logbitp 0 1.0)", "(wrong-type-argument integerp 1.0)");
+    }
+
+    #[test]
+    fn test_number_sequence() {
+        assert_lisp("(number-sequence 1 5)", "(1 2 3 4 5)");
+        assert_lisp("(number-sequence 1 6 2)", "(1 3 5)");
+        assert_lisp("(number-sequence 5 1 -2)", "(5 3 1)");
+        assert_lisp("(number-sequence 3)", "(3)");
+        assert_lisp("(number-sequence 3 3.0 7)", "(3)");
+        assert_lisp("(number-sequence 5 1)", "nil");
+        assert_lisp("(number-sequence 0 -1 2)", "nil");
+        assert_lisp("(number-sequence 1 3.5)", "(1 2 3)");
+        assert_lisp("(number-sequence 1 2 0.5)", "(1 1.5 2.0)");
+        assert_lisp_signal("(number-sequence 1 2 0)", "(error \"The increment can not be zero\")");
+        assert_lisp("(number-sequence 0 (/ 0.0 0.0))", "nil");
+        assert_lisp_signal(
+            &format!("(number-sequence 0 {MAX_ARRAY_SIZE})"),
+            "(error \"Maximum buffer/string size exceeded\")",
+        );
+        assert_lisp_signal(
+            "(number-sequence 0 (/ 1.0 0.0))",
+            "(error \"Maximum buffer/string size exceeded\")",
+        );
+    }
+
+    #[test]
+    fn test_number_sequence_floats() {
+        // each element is computed from the start, so there is no drift
+        assert_lisp(
+            "(number-sequence 0.0 1.0 0.1)",
+            "(0.0 0.1 0.2 0.30000000000000004 0.4 0.5 0.6000000000000001 \
+             0.7000000000000001 0.8 0.9 1.0)",
+        );
+        assert_lisp("(length (number-sequence 0.0 1.0 0.1))", "11");
+        let multiples = "(mapcar (lambda (n) (* n 0.1)) (number-sequence 0 10))";
+        assert_lisp(&format!("(equal (number-sequence 0.0 1.0 0.1) {multiples})"), "t");
+        assert_lisp("(number-sequence 1.0 0.0 -0.25)", "(1.0 0.75 0.5 0.25 0.0)");
+        // the end is only included if a step lands on it exactly
+        assert_lisp("(number-sequence 0.0 0.3 0.1)", "(0.0 0.1 0.2)");
+        assert_lisp("(length (number-sequence 0 100.0 0.01))", "10001");
+    }
+
+    #[test]
+    fn test_cmp_total() {
+        use NumberValue as N;
+        use Ordering::{Equal, Greater, Less};
+        let nan = N::Float(f64::NAN);
+        assert_eq!(N::Float(-0.0).partial_cmp(&N::Float(0.0)), Some(Equal));
+        assert_eq!(N::Float(-0.0).cmp_total(&N::Float(0.0)), Equal);
+        assert_eq!(N::Int(0).cmp_total(&N::Float(-0.0)), Equal);
+        assert_eq!(nan.partial_cmp(&N::Int(1)), None);
+        assert_eq!(nan.cmp_total(&N::Int(1)), Greater);
+        assert_eq!(N::Float(f64::INFINITY).cmp_total(&nan), Less);
+        assert_eq!(nan.cmp_total(&N::Float(-f64::NAN)), Equal);
+        assert_eq!(N::Int(1).cmp_total(&N::Float(1.5)), Less);
+        assert_eq!(N::Big(BigInt::from(1) << 70).cmp_total(&N::Float(2.0)), Greater);
+    }
+
+    /// Property tests that run random sequences of arithmetic and check them
+    /// against exact oracles, covering fixnum overflow, bignum normalization,
+    /// and float contagion together.
+    mod fuzz {
+        use super::*;
+        use proptest::prelude::*;
+
+        #[derive(Debug, Clone, Copy)]
+        enum Op {
+            Add,
+            Sub,
+            Mul,
+        }
+
+        /// The value that a sequence of operations should produce. Integers
+        /// are exact, and once a float is involved the result is a float.
+        #[derive(Debug, Clone)]
+        enum Oracle {
+            Int(BigInt),
+            Float(f64),
+        }
+
+        impl Oracle {
+            fn to_f64(&self) -> f64 {
+                match self {
+                    Oracle::Int(x) => x.to_f64().unwrap(),
+                    Oracle::Float(x) => *x,
+                }
+            }
+
+            fn apply(self, op: Op, rhs: &Oracle) -> Oracle {
+                match (self, rhs) {
+                    (Oracle::Int(l), Oracle::Int(r)) => Oracle::Int(match op {
+                        Op::Add => l + r,
+                        Op::Sub => l - r,
+                        Op::Mul => l * r,
+                    }),
+                    (l, r) => {
+                        let (l, r) = (l.to_f64(), r.to_f64());
+                        Oracle::Float(match op {
+                            Op::Add => l + r,
+                            Op::Sub => l - r,
+                            Op::Mul => l * r,
+                        })
+                    }
+                }
+            }
+
+            fn to_value(&self) -> NumberValue {
+                match self {
+                    Oracle::Int(x) => NumberValue::Big(x.clone()).normalize(),
+                    Oracle::Float(x) => NumberValue::Float(*x),
+                }
+            }
+        }
+
+        fn apply(op: Op, lhs: NumberValue, rhs: NumberValue) -> NumberValue {
+            match op {
+                Op::Add => lhs + rhs,
+                Op::Sub => lhs - rhs,
+                Op::Mul => lhs * rhs,
+            }
+        }
+
+        fn op() -> impl Strategy<Value = Op> {
+            prop_oneof![Just(Op::Add), Just(Op::Sub), Just(Op::Mul)]
+        }
+
+        fn integer() -> impl Strategy<Value = BigInt> {
+            let sign = |negative| if negative { Sign::Minus } else { Sign::Plus };
+            prop_oneof![
+                (MIN_FIXNUM..=MAX_FIXNUM).prop_map(BigInt::from),
+                // the values on either side of the fixnum limits
+                (-4_i64..=4).prop_map(|x| BigInt::from(MAX_FIXNUM) + x),
+                (-4_i64..=4).prop_map(|x| BigInt::from(MIN_FIXNUM) + x),
+                (any::<bool>(), prop::collection::vec(any::<u32>(), 1..4))
+                    .prop_map(move |(negative, digits)| BigInt::new(sign(negative), digits)),
+            ]
+        }
+
+        fn operand() -> impl Strategy<Value = Oracle> {
+            prop_oneof![
+                3 => integer().prop_map(Oracle::Int),
+                1 => (-1e6..1e6_f64).prop_map(Oracle::Float),
+            ]
+        }
+
+        proptest! {
+            // Two fixnums always fit in an i128, so the exact result is known
+            // and only needs a bignum when it leaves the fixnum range.
+            #[test]
+            fn fixnum_arithmetic(
+                l in MIN_FIXNUM..=MAX_FIXNUM,
+                r in MIN_FIXNUM..=MAX_FIXNUM,
+                op in op(),
+            ) {
+                let (wide_l, wide_r) = (i128::from(l), i128::from(r));
+                let exact = match op {
+                    Op::Add => wide_l + wide_r,
+                    Op::Sub => wide_l - wide_r,
+                    Op::Mul => wide_l * wide_r,
+                };
+                let expected = match i64::try_from(exact) {
+                    Ok(x) if (MIN_FIXNUM..=MAX_FIXNUM).contains(&x) => NumberValue::Int(x),
+                    _ => NumberValue::Big(BigInt::from(exact)),
+                };
+                prop_assert_eq!(apply(op, NumberValue::Int(l), NumberValue::Int(r)), expected);
+            }
+
+            #[test]
+            fn arithmetic_sequence(
+                start in operand(),
+                steps in prop::collection::vec((op(), operand()), 1..6),
+            ) {
+                let mut actual = start.to_value();
+                let mut expected = start;
+                for (op, rhs) in steps {
+                    actual = apply(op, actual, rhs.to_value());
+                    expected = expected.apply(op, &rhs);
+                    prop_assert!(actual.is_normalized(), "not normalized: {:?}", actual);
+                    match (&actual, &expected) {
+                        (NumberValue::Float(x), Oracle::Float(y)) => {
+                            prop_assert!(x.approx_eq(*y, (0.0, 4)), "{} != {}", x, y);
+                        }
+                        (_, Oracle::Float(_)) => prop_assert!(false, "{:?} is not a float", actual),
+                        (_, Oracle::Int(_)) => prop_assert_eq!(&actual, &expected.to_value()),
+                    }
+                }
+            }
+        }
     }
 }
@@ -1,7 +1,8 @@
 //! Buffer operations.
 use crate::{
+    character::string_text,
     core::{
-        env::{Env, INTERNED_SYMBOLS},
+        env::{Env, INTERNED_SYMBOLS, sym},
         error::{Type, TypeError},
         gc::{Context, Rt},
         object::{Gc, LispBuffer, NIL, Object, ObjectType, OptionalFlag},
@@ -32,14 +33,16 @@ pub(crate) fn set_buffer<'ob>(
 fn resolve_buffer<'ob>(buffer_or_name: Object, cx: &'ob Context) -> Result<&'ob LispBuffer> {
     match buffer_or_name.untag() {
         ObjectType::Buffer(b) => Ok(b),
-        ObjectType::String(name) => {
+        _ => {
+            let Some(name) = string_text(buffer_or_name) else {
+                return Err(TypeError::new(Type::String, buffer_or_name).into());
+            };
             let buffer_list = BUFFERS.lock().unwrap();
-            let Some(buffer) = buffer_list.get(name.as_ref()) else {
+            let Some(buffer) = buffer_list.get(&*name) else {
                 bail!("No buffer named {}", name);
             };
             Ok(cx.bind(*buffer))
         }
-        x => Err(TypeError::new(Type::String, x).into()),
     }
 }
 
@@ -98,28 +101,30 @@ pub(crate) fn get_buffer_create<'ob>(
     cx: &'ob Context,
 ) -> Result<Object<'ob>> {
     match buffer_or_name.untag() {
-        ObjectType::String(name) => {
+        ObjectType::Buffer(_) => Ok(buffer_or_name),
+        _ => {
+            let Some(name) = string_text(buffer_or_name) else {
+                return Err(TypeError::new(Type::BufferOrName, buffer_or_name).into());
+            };
             let mut buffer_list = BUFFERS.lock().unwrap();
-            match buffer_list.get(name.as_ref()) {
+            match buffer_list.get(&*name) {
                 Some(b) => Ok(cx.add(*b)),
                 None => {
                     // If not already in the global buffer list, create a new
                     // buffer and add it
                     let buffer: &'static _ = {
                         let global = INTERNED_SYMBOLS.lock().unwrap();
-                        let buffer = global.create_buffer(name);
+                        let buffer = global.create_buffer(&name);
                         // SAFETY: This can be 'static because it is stored in the
                         // global block. Eventually it will be garbage collected
                         unsafe { &*(buffer as *const LispBuffer) }
                     };
-                    buffer_list.insert(name.to_string(), buffer);
+                    buffer_list.insert(name.into_owned(), buffer);
                     let buf = cx.add(buffer);
                     Ok(buf)
                 }
             }
         }
-        ObjectType::Buffer(_) => Ok(buffer_or_name),
-        other => Err(TypeError::new(Type::BufferOrName, other).into()),
     }
 }
 
@@ -129,15 +134,17 @@ pub(crate) fn get_buffer<'ob>(
     cx: &'ob Context,
 ) -> Result<Object<'ob>> {
     match buffer_or_name.untag() {
-        ObjectType::String(name) => {
+        ObjectType::Buffer(_) => Ok(buffer_or_name),
+        _ => {
+            let Some(name) = string_text(buffer_or_name) else {
+                return Err(TypeError::new(Type::BufferOrName, buffer_or_name).into());
+            };
             let buffer_list = BUFFERS.lock().unwrap();
-            match buffer_list.get(name.as_ref()) {
+            match buffer_list.get(&*name) {
                 Some(b) => Ok(cx.add(*b)),
                 None => Ok(NIL),
             }
         }
-        ObjectType::Buffer(_) => Ok(buffer_or_name),
-        other => Err(TypeError::new(Type::BufferOrName, other).into()),
     }
 }
 
@@ -228,6 +235,22 @@ defvar!(TRUNCATE_LINES);
 defvar!(WORD_WRAP);
 defvar!(BIDI_DISPLAY_REORDERING);
 defvar!(BUFFER_FILE_NAME);
+defvar!(CASE_FOLD_SEARCH, true);
+
+/// The width of a tab stop from `tab-width`. Like Emacs, values that are not
+/// a reasonable positive integer use the default of 8 columns.
+pub(crate) fn tab_width(env: &Rt<Env>, cx: &Context) -> usize {
+    match env.vars.get(sym::TAB_WIDTH).map(|x| x.untag(cx)) {
+        Some(ObjectType::Int(width @ 1..=1000)) => width as usize,
+        _ => 8,
+    }
+}
+
+/// Whether searches and comparisons should ignore case, based on the current
+/// binding of `case-fold-search`. Like Emacs, this defaults to true.
+pub(crate) fn case_fold_search(env: &Rt<Env>) -> bool {
+    env.vars.get(sym::CASE_FOLD_SEARCH).is_none_or(|x| x != &sym::NIL)
+}
 
 #[cfg(test)]
 mod test {
@@ -283,7 +283,8 @@ impl<'ob> RootedVM<'_, '_, '_> {
                 } else {
                     // TODO: Need to remove the anyhow branch once
                     // full errors are implemented
-                    Cons::new(sym::ERROR, format!("{err}"), cx)
+                    let message = Cons::new1(format!("{err}"), cx);
+                    Cons::new(sym::ERROR, message, cx)
                 };
                 self.unwind(handler.stack_frame, cx);
                 self.env.stack.truncate(handler.stack_size);
@@ -586,13 +587,13 @@ impl<'ob> RootedVM<'_, '_, '_> {
                     let arg1 = self.env.stack.pop(cx);
                     let top = self.env.stack.top();
                     let args = &[arg1.try_into()?];
-                    top.set(cx.add(arith::max(top.bind_as(cx)?, args)));
+                    top.set(arith::max(top.bind_as(cx)?, args, cx));
                 }
                 op::Min => {
                     let arg1 = self.env.stack.pop(cx);
                     let top = self.env.stack.top();
                     let args = &[arg1.try_into()?];
-                    top.set(cx.add(arith::min(top.bind_as(cx)?, args)));
+                    top.set(arith::min(top.bind_as(cx)?, args, cx));
                 }
                 op::Multiply => {
                     let arg1 = self.env.stack.pop(cx);
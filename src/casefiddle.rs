@@ -1,9 +1,10 @@
 //! String and character case conversion.
 use std::ops::Range;
 
+use crate::character::{char_to_raw_byte, unibyte_to_char};
 use crate::core::{
     gc::Rt,
-    object::{NIL, Object},
+    object::{NIL, Object, RawBytesText},
 };
 use crate::fns::StringOrChar;
 use crate::{Context, Env};
@@ -14,6 +15,8 @@ use text_buffer::Buffer as TextBuffer;
 fn capitalize<'ob>(string_or_char: StringOrChar<'ob>, cx: &'ob Context<'ob>) -> Object<'ob> {
     match string_or_char {
         StringOrChar::String(s) => cx.add(casify_string(s, CaseMode::Capitalize)),
+        StringOrChar::RawBytes(s) => cx.add(RawBytesText(casify_string(s, CaseMode::Capitalize))),
+        StringOrChar::ByteString(s) => cx.add(casify_bytes(s, CaseMode::Capitalize)),
         StringOrChar::Char(c) => cx.add(casify_char(c, char::to_uppercase)),
     }
 }
@@ -22,6 +25,8 @@ fn capitalize<'ob>(string_or_char: StringOrChar<'ob>, cx: &'ob Context<'ob>) ->
 fn upcase<'ob>(string_or_char: StringOrChar<'ob>, cx: &'ob Context<'ob>) -> Object<'ob> {
     match string_or_char {
         StringOrChar::String(s) => cx.add(casify_string(s, CaseMode::Upcase)),
+        StringOrChar::RawBytes(s) => cx.add(RawBytesText(casify_string(s, CaseMode::Upcase))),
+        StringOrChar::ByteString(s) => cx.add(casify_bytes(s, CaseMode::Upcase)),
         StringOrChar::Char(c) => cx.add(casify_char(c, char::to_uppercase)),
     }
 }
@@ -30,6 +35,8 @@ fn upcase<'ob>(string_or_char: StringOrChar<'ob>, cx: &'ob Context<'ob>) -> Obje
 fn downcase<'ob>(string_or_char: StringOrChar<'ob>, cx: &'ob Context<'ob>) -> Object<'ob> {
     match string_or_char {
         StringOrChar::String(s) => cx.add(casify_string(s, CaseMode::Downcase)),
+        StringOrChar::RawBytes(s) => cx.add(RawBytesText(casify_string(s, CaseMode::Downcase))),
+        StringOrChar::ByteString(s) => cx.add(casify_bytes(s, CaseMode::Downcase)),
         StringOrChar::Char(c) => cx.add(casify_char(c, char::to_lowercase)),
     }
 }
@@ -38,6 +45,10 @@ fn downcase<'ob>(string_or_char: StringOrChar<'ob>, cx: &'ob Context<'ob>) -> Ob
 fn upcase_initials<'ob>(string_or_char: StringOrChar<'ob>, cx: &'ob Context<'ob>) -> Object<'ob> {
     match string_or_char {
         StringOrChar::String(s) => cx.add(casify_string(s, CaseMode::UpcaseInitials)),
+        StringOrChar::RawBytes(s) => {
+            cx.add(RawBytesText(casify_string(s, CaseMode::UpcaseInitials)))
+        }
+        StringOrChar::ByteString(s) => cx.add(casify_bytes(s, CaseMode::UpcaseInitials)),
         StringOrChar::Char(c) => cx.add(casify_char(c, char::to_uppercase)),
     }
 }
@@ -96,34 +107,45 @@ fn capitalize_word<'ob>(offset: i64, env: &mut Rt<Env>) -> Object<'ob> {
     NIL
 }
 
+/// Change the case of `s` a word at a time. Without a syntax table, a word is
+/// a run of alphanumeric characters. An apostrophe between two of them is
+/// part of the word, so "it's" is one word and not two.
 fn casify_string(s: &str, mode: CaseMode) -> String {
     let mut out = String::with_capacity(s.len());
-
-    for word in s.split_inclusive(|c: char| precedes_capitalization(c)) {
-        let mut chars = word.chars();
-        if let Some(c) = chars.next() {
-            match mode {
-                CaseMode::Downcase => out.extend(c.to_lowercase()),
-                CaseMode::Upcase | CaseMode::Capitalize | CaseMode::UpcaseInitials => {
-                    out.extend(c.to_uppercase())
-                }
+    let mut in_word = false;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let starts_word = !in_word && c.is_alphanumeric();
+        in_word = c.is_alphanumeric()
+            || (in_word && is_apostrophe(c) && chars.peek().is_some_and(|c| c.is_alphanumeric()));
+        match (mode, starts_word) {
+            (CaseMode::Downcase, _) | (CaseMode::Capitalize, false) => {
+                out.extend(c.to_lowercase());
             }
-        }
-        for c in chars {
-            match mode {
-                CaseMode::Upcase => out.extend(c.to_uppercase()),
-                CaseMode::Downcase | CaseMode::Capitalize => out.extend(c.to_lowercase()),
-                CaseMode::UpcaseInitials => out.push(c),
+            (CaseMode::Upcase, _) | (CaseMode::Capitalize | CaseMode::UpcaseInitials, true) => {
+                out.extend(c.to_uppercase());
             }
+            (CaseMode::UpcaseInitials, false) => out.push(c),
         }
     }
     out
 }
 
-fn precedes_capitalization(c: char) -> bool {
-    !c.is_alphanumeric()
+/// Only ASCII letters have case in a unibyte string, so the result is still
+/// unibyte. The other bytes are raw bytes, which are left alone.
+fn casify_bytes(bytes: &[u8], mode: CaseMode) -> Vec<u8> {
+    let string: String = bytes.iter().map(|&b| unibyte_to_char(b)).collect();
+    let to_byte = |c: char| {
+        char_to_raw_byte(c).unwrap_or_else(|| u8::try_from(c).expect("ASCII case is ASCII"))
+    };
+    casify_string(&string, mode).chars().map(to_byte).collect()
+}
+
+fn is_apostrophe(c: char) -> bool {
+    matches!(c, '\'' | '\u{2019}')
 }
 
+#[derive(Clone, Copy)]
 enum CaseMode {
     Downcase,
     Upcase,
@@ -131,6 +153,9 @@ enum CaseMode {
     UpcaseInitials,
 }
 
+/// Change the case of a single character. Only simple case mappings are used,
+/// since a character can't become several; full mappings like `ß` to `SS`
+/// only happen for strings.
 fn casify_char<T>(c: u64, f: impl Fn(char) -> T) -> u64
 where
     T: Iterator<Item = char>,
@@ -228,6 +253,25 @@ mod tests {
         assert_eq!(upcase(StringOrChar::Char(u64::MAX), cx), cx.add(u64::MAX));
     }
 
+    #[test]
+    fn test_simple_and_full_mapping() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        // chars whose case expands to several chars are left alone
+        assert_eq!(upcase('ß'.into(), cx), 'ß');
+        assert_eq!(upcase("ß".into(), cx), "SS");
+        assert_eq!(upcase("straße".into(), cx), "STRASSE");
+        assert_eq!(upcase('ŉ'.into(), cx), 'ŉ');
+        assert_eq!(upcase("ŉ".into(), cx), "\u{2BC}N");
+        assert_eq!(downcase('İ'.into(), cx), 'İ');
+        assert_eq!(downcase("İ".into(), cx), "i\u{307}");
+        // single char mappings apply in both modes
+        assert_eq!(upcase('ǆ'.into(), cx), 'Ǆ');
+        assert_eq!(upcase("ǆ".into(), cx), "Ǆ");
+        assert_eq!(downcase('Σ'.into(), cx), 'σ');
+        assert_eq!(downcase("Σ".into(), cx), "σ");
+    }
+
     #[test]
     fn test_capitalize() {
         let roots = &RootSet::default();
@@ -264,6 +308,26 @@ mod tests {
         // assert_eq!(capitalize("𐩐𐒰", cx), Ok("𐩐𐓘"));
     }
 
+    #[test]
+    fn test_capitalize_words() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        // apostrophes inside a word don't start a new one
+        assert_eq!(capitalize("it's".into(), cx), "It's");
+        assert_eq!(capitalize("DON'T STOP".into(), cx), "Don't Stop");
+        assert_eq!(capitalize("o\u{2019}neil".into(), cx), "O\u{2019}neil");
+        assert_eq!(upcase_initials("it's".into(), cx), "It's");
+        // but quotes around a word are not part of it
+        assert_eq!(capitalize("'quoted' words'".into(), cx), "'Quoted' Words'");
+        assert_eq!(capitalize("rock 'n' roll".into(), cx), "Rock 'N' Roll");
+        // digits are word constituents
+        assert_eq!(capitalize("1st 2ND x1y".into(), cx), "1st 2nd X1y");
+        // other punctuation separates words
+        assert_eq!(capitalize("foo-bar_baz".into(), cx), "Foo-Bar_Baz");
+        assert_eq!(capitalize("well-KNOWN".into(), cx), "Well-Known");
+        assert_eq!(upcase_initials("foo-bar".into(), cx), "Foo-Bar");
+    }
+
     #[test]
     fn test_upcase_initials() {
         let roots = &RootSet::default();
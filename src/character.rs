@@ -1,10 +1,523 @@
 //! Character and string utilities.
-use crate::core::{
-    gc::Context,
-    object::{Gc, Object, OptionalFlag, int_to_char},
+use crate::{
+    alloc::check_array_size,
+    buffer::tab_width,
+    core::{
+        env::{Env, sym},
+        error::{Type, TypeError},
+        gc::{Block, Context, Rt},
+        object::{
+            Gc, IntoObject, LispString, Object, ObjectType, OptionalFlag, RawBytesText, Supplied,
+        },
+    },
+    data::LispError,
 };
-use anyhow::Result;
+use anyhow::{Result, bail, ensure};
 use rune_macros::defun;
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+/// Multibyte strings are stored as UTF-8, which has no encoding for the raw
+/// bytes that Emacs calls eight-bit characters (`#x3FFF80` to `#x3FFFFF`). A
+/// string that holds raw bytes is marked when it is made, and in it the last
+/// 128 code points of plane 16 (U+10FF80 to U+10FFFF) stand in for them,
+/// chosen so that the low byte of the stand-in char is the raw byte. In any
+/// other string those code points are ordinary chars, which is why a single
+/// string can't hold both.
+const RAW_BYTE_BASE: u32 = 0x10_FF00;
+
+/// The character code for raw byte 0, so that raw byte `b` is `EIGHT_BIT_BASE + b`.
+pub(crate) const EIGHT_BIT_BASE: i64 = 0x3F_FF00;
+
+/// The largest character code, which is the last eight-bit character.
+pub(crate) const MAX_CHAR: i64 = 0x3F_FFFF;
+
+/// The largest Unicode character code.
+pub(crate) const MAX_UNICODE_CHAR: i64 = 0x10_FFFF;
+
+/// Return the char used to store the raw byte `byte` in a string that holds
+/// raw bytes.
+pub(crate) fn raw_byte_to_char(byte: u8) -> char {
+    debug_assert!(!byte.is_ascii(), "ASCII bytes are not raw bytes");
+    char::from_u32(RAW_BYTE_BASE + u32::from(byte)).unwrap()
+}
+
+/// If `chr` is one of the chars that stand in for raw bytes, return the raw
+/// byte. It only means that byte in a string that holds raw bytes.
+pub(crate) fn char_to_raw_byte(chr: char) -> Option<u8> {
+    let code = u32::from(chr);
+    (code >= RAW_BYTE_BASE + 0x80).then(|| (code - RAW_BYTE_BASE) as u8)
+}
+
+/// The character code of `chr` in a multibyte string. If the string holds
+/// raw bytes (`raw_bytes`), a stand-in char has the eight-bit code for its
+/// byte.
+pub(crate) fn char_code(chr: char, raw_bytes: bool) -> i64 {
+    match char_to_raw_byte(chr) {
+        Some(byte) if raw_bytes => EIGHT_BIT_BASE + i64::from(byte),
+        _ => i64::from(u32::from(chr)),
+    }
+}
+
+/// Whether `code` is an eight-bit character, which is a raw byte.
+pub(crate) fn is_eight_bit(code: i64) -> bool {
+    (EIGHT_BIT_BASE + 0x80..=MAX_CHAR).contains(&code)
+}
+
+/// The Unicode char with the code `code`. Eight-bit characters are not
+/// chars on their own, they are only stored in strings by [`MultibyteText`].
+pub(crate) fn code_to_char(code: i64) -> Option<char> {
+    u32::try_from(code).ok().and_then(char::from_u32)
+}
+
+/// Check that `obj` is a character code, signaling `(wrong-type-argument
+/// characterp obj)` like Emacs if it is not.
+pub(crate) fn require_character(obj: Object, cx: &Context) -> Result<i64> {
+    match obj.untag() {
+        ObjectType::Int(code @ 0..=MAX_CHAR) => Ok(code),
+        _ => Err(LispError::wrong_type(sym::CHARACTERP, obj, cx).into()),
+    }
+}
+
+/// The character code of a byte of a unibyte string when it is in a
+/// multibyte string. ASCII is unchanged and everything else becomes a raw
+/// byte.
+pub(crate) fn unibyte_to_code(byte: u8) -> i64 {
+    if byte.is_ascii() { i64::from(byte) } else { EIGHT_BIT_BASE + i64::from(byte) }
+}
+
+/// The char that stores a byte of a unibyte string in text that holds raw
+/// bytes. ASCII is unchanged and everything else becomes a raw byte.
+pub(crate) fn unibyte_to_char(byte: u8) -> char {
+    if byte.is_ascii() { char::from(byte) } else { raw_byte_to_char(byte) }
+}
+
+/// The text of `string`, or `None` if it is not a string. The non-ASCII bytes
+/// of a unibyte string become the chars that stand in for raw bytes, so only
+/// those strings are copied. The text doesn't say whether those chars are raw
+/// bytes, so it is only meant for things like names. Text that goes into a new
+/// string is added with [`MultibyteText::push_string`] instead.
+pub(crate) fn string_text(string: Object) -> Option<Cow<'_, str>> {
+    match string.untag() {
+        ObjectType::String(string) => Some(Cow::Borrowed(string.inner())),
+        ObjectType::ByteString(bytes) if bytes.is_ascii() => {
+            Some(Cow::Borrowed(std::str::from_utf8(bytes).unwrap()))
+        }
+        ObjectType::ByteString(bytes) => Some(bytes.iter().map(|&b| unibyte_to_char(b)).collect()),
+        _ => None,
+    }
+}
+
+/// Whether the stand-in chars in the [`string_text`] of `string` are raw
+/// bytes.
+pub(crate) fn text_has_raw_bytes(string: Object) -> bool {
+    match string.untag() {
+        ObjectType::String(string) => string.has_raw_bytes(),
+        ObjectType::ByteString(bytes) => !bytes.is_ascii(),
+        _ => false,
+    }
+}
+
+const MIXED_RAW_BYTES: &str = "Raw bytes can't be in a string with the chars U+10FF80 to U+10FFFF";
+
+/// The text of a new multibyte string, built from character codes and the
+/// text of other strings. It keeps track of whether the text holds raw bytes,
+/// so adding raw bytes to text that has one of the chars that stand in for
+/// them, or the other way around, is an error.
+#[derive(Default)]
+pub(crate) struct MultibyteText {
+    text: String,
+    raw_bytes: bool,
+    stand_ins: bool,
+}
+
+impl MultibyteText {
+    /// Add the character with the code `code`, which can be eight-bit.
+    pub(crate) fn push_code(&mut self, code: i64) -> Result<()> {
+        if is_eight_bit(code) {
+            let chr = raw_byte_to_char((code - EIGHT_BIT_BASE) as u8);
+            return self.push_str(chr.encode_utf8(&mut [0; 4]), true);
+        }
+        let Some(chr) = code_to_char(code) else {
+            bail!("Character {code:#x} can't be stored in a string")
+        };
+        self.push_str(chr.encode_utf8(&mut [0; 4]), false)
+    }
+
+    /// Add `text`, where the stand-in chars are raw bytes if `raw_bytes`.
+    pub(crate) fn push_str(&mut self, text: &str, raw_bytes: bool) -> Result<()> {
+        self.check(text, raw_bytes)?;
+        self.text.push_str(text);
+        Ok(())
+    }
+
+    /// Check that `text` can be added, without adding it.
+    fn check(&mut self, text: &str, raw_bytes: bool) -> Result<()> {
+        if text.chars().any(|c| char_to_raw_byte(c).is_some()) {
+            if raw_bytes {
+                self.raw_bytes = true;
+            } else {
+                self.stand_ins = true;
+            }
+            ensure!(!(self.raw_bytes && self.stand_ins), MIXED_RAW_BYTES);
+        }
+        Ok(())
+    }
+
+    /// Add the text of `string`. The non-ASCII bytes of a unibyte string are
+    /// added as raw bytes.
+    pub(crate) fn push_string(&mut self, string: Object) -> Result<()> {
+        match string_text(string) {
+            Some(text) => self.push_str(&text, text_has_raw_bytes(string)),
+            None => Err(TypeError::new(Type::String, string).into()),
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub(crate) fn has_raw_bytes(&self) -> bool {
+        self.raw_bytes
+    }
+}
+
+/// Whether the [`string_text`] of any of `strings` has raw bytes. Their texts
+/// can only be combined if the stand-in chars mean the same thing in all of
+/// them, so it is an error if another one has the chars themselves.
+pub(crate) fn texts_have_raw_bytes(strings: &[Object]) -> Result<bool> {
+    let mut combined = MultibyteText::default();
+    for &string in strings {
+        if let Some(text) = string_text(string) {
+            combined.check(&text, text_has_raw_bytes(string))?;
+        }
+    }
+    Ok(combined.raw_bytes)
+}
+
+impl IntoObject for MultibyteText {
+    type Out<'ob> = &'ob LispString;
+
+    fn into_obj<const C: bool>(self, block: &Block<C>) -> Gc<Self::Out<'_>> {
+        if self.raw_bytes {
+            RawBytesText(self.text).into_obj(block)
+        } else {
+            self.text.into_obj(block)
+        }
+    }
+}
+
+/// Change the case of `chr` with `case`, unless it maps to several chars.
+fn single_case<T: Iterator<Item = char>>(chr: char, case: impl Fn(char) -> T) -> char {
+    let mut cased = case(chr);
+    match (cased.next(), cased.next()) {
+        (Some(cased), None) => cased,
+        _ => chr,
+    }
+}
+
+/// Fold the case of `chr` for a case-insensitive comparison. Two chars fold
+/// the same when Unicode simple case folding maps them together, so `ς`
+/// matches `σ` and `ſ` matches `s`, but `ß` never matches `"ss"`. A char
+/// always folds to a single char, and that char is uppercase because
+/// `compare-strings` orders strings by their upcased chars.
+pub(crate) fn fold_char(chr: char) -> impl Iterator<Item = char> {
+    // dotless i only folds with I in Turkic languages
+    let folded = if chr == 'ı' {
+        chr
+    } else {
+        single_case(single_case(chr, char::to_lowercase), char::to_uppercase)
+    };
+    std::iter::once(folded)
+}
+
+/// An iterator over the elements of a string as `aref` sees them. A multibyte
+/// string gives the code of each char, so the raw bytes of a string that holds
+/// them are their eight-bit code, and a unibyte string gives its bytes.
+#[derive(Clone)]
+pub(crate) enum StringCodes<'a> {
+    Multibyte(std::str::Chars<'a>, bool),
+    Unibyte(std::slice::Iter<'a, u8>),
+}
+
+impl<'a> StringCodes<'a> {
+    pub(crate) fn is_multibyte(&self) -> bool {
+        matches!(self, Self::Multibyte(..))
+    }
+
+    /// The codes the elements have in a multibyte string, where the non-ASCII
+    /// bytes of a unibyte string are eight-bit characters.
+    pub(crate) fn as_multibyte(self) -> impl DoubleEndedIterator<Item = i64> + Clone + 'a {
+        let unibyte = !self.is_multibyte();
+        self.map(move |code| if unibyte { unibyte_to_code(code as u8) } else { code })
+    }
+}
+
+impl Iterator for StringCodes<'_> {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        match self {
+            Self::Multibyte(chars, raw_bytes) => chars.next().map(|c| char_code(c, *raw_bytes)),
+            Self::Unibyte(bytes) => bytes.next().map(|&b| i64::from(b)),
+        }
+    }
+
+    // forwarded so unibyte strings keep constant time indexing
+    fn nth(&mut self, n: usize) -> Option<i64> {
+        match self {
+            Self::Multibyte(chars, raw_bytes) => chars.nth(n).map(|c| char_code(c, *raw_bytes)),
+            Self::Unibyte(bytes) => bytes.nth(n).map(|&b| i64::from(b)),
+        }
+    }
+
+    fn count(self) -> usize {
+        match self {
+            Self::Multibyte(chars, _) => chars.count(),
+            Self::Unibyte(bytes) => bytes.len(),
+        }
+    }
+}
+
+impl DoubleEndedIterator for StringCodes<'_> {
+    fn next_back(&mut self) -> Option<i64> {
+        match self {
+            Self::Multibyte(chars, raw_bytes) => {
+                chars.next_back().map(|c| char_code(c, *raw_bytes))
+            }
+            Self::Unibyte(bytes) => bytes.next_back().map(|&b| i64::from(b)),
+        }
+    }
+}
+
+/// The element codes of `string`, or `None` if it is not a string.
+pub(crate) fn string_codes(string: Object) -> Option<StringCodes> {
+    match string.untag() {
+        ObjectType::String(string) => {
+            Some(StringCodes::Multibyte(string.chars(), string.has_raw_bytes()))
+        }
+        ObjectType::ByteString(string) => Some(StringCodes::Unibyte(string.iter())),
+        _ => None,
+    }
+}
+
+/// Build a string from codes given by [`StringCodes`]. A multibyte string
+/// takes them as characters and a unibyte string as bytes.
+pub(crate) fn string_from_codes<'ob>(
+    codes: impl Iterator<Item = i64>,
+    multibyte: bool,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    if multibyte {
+        let mut text = MultibyteText::default();
+        for code in codes {
+            text.push_code(code)?;
+        }
+        Ok(cx.add(text))
+    } else {
+        Ok(cx.add(codes.map(|x| x as u8).collect::<Vec<u8>>()))
+    }
+}
+
+/// The number of columns used to display `chr`. Tabs are not handled here,
+/// since their width depends on the column they start at.
+pub(crate) fn char_display_width(chr: char) -> usize {
+    match chr {
+        '\n' => 0,
+        // control characters are displayed like ^A
+        '\0'..='\x1F' | '\x7F' => 2,
+        // C1 controls are displayed as octal escapes like \200
+        '\u{80}'..='\u{9F}' => 4,
+        _ => chr.width().unwrap_or(1),
+    }
+}
+
+/// The number of columns used to display the grapheme cluster `cluster`.
+/// This approximates how terminals draw emoji sequences: a flag (a pair of
+/// regional indicators) or emoji joined with zero width joiners is drawn as a
+/// single wide emoji. Any other cluster is as wide as its chars, where
+/// combining marks and variation selectors take no columns.
+fn cluster_display_width(cluster: &str) -> usize {
+    let is_regional_indicator = |c| ('\u{1F1E6}'..='\u{1F1FF}').contains(&c);
+    let mut chars = cluster.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => 0,
+        (Some(chr), None) => char_display_width(chr),
+        _ if cluster.chars().all(is_regional_indicator) => 2,
+        _ if cluster.contains('\u{200D}') && cluster.chars().any(|c| c.width() == Some(2)) => 2,
+        _ => cluster.chars().map(char_display_width).sum(),
+    }
+}
+
+/// The column after displaying the grapheme cluster `cluster` at `column`.
+/// If the text holds raw bytes (`raw_bytes`), their stand-in chars are
+/// displayed as octal escapes like \377.
+fn next_column(column: usize, cluster: &str, raw_bytes: bool, tab_width: usize) -> usize {
+    let is_raw_byte = |chr| raw_bytes && char_to_raw_byte(chr).is_some();
+    match cluster {
+        "\t" => (column / tab_width + 1) * tab_width,
+        cluster if cluster.chars().any(is_raw_byte) => {
+            let width = |chr| if is_raw_byte(chr) { 4 } else { char_display_width(chr) };
+            column + cluster.chars().map(width).sum::<usize>()
+        }
+        cluster => column + cluster_display_width(cluster),
+    }
+}
+
+/// The width of `string` when displayed starting at column 0. A tab advances
+/// to the next multiple of `tab_width`, so its width depends on the
+/// characters before it.
+pub(crate) fn display_width(string: &str, raw_bytes: bool, tab_width: usize) -> usize {
+    string
+        .graphemes(true)
+        .fold(0, |column, cluster| next_column(column, cluster, raw_bytes, tab_width))
+}
+
+#[defun]
+fn char_width(chr: Object, env: &Rt<Env>, cx: &Context) -> Result<usize> {
+    let code = require_character(chr, cx)?;
+    // raw bytes are displayed as octal escapes like \377, and other codes
+    // outside of Unicode take a single column
+    Ok(match code_to_char(code) {
+        Some('\t') => tab_width(env, cx),
+        Some(chr) => char_display_width(chr),
+        None if is_eight_bit(code) => 4,
+        None => 1,
+    })
+}
+
+#[defun]
+fn string_width(
+    string: Object,
+    from: Option<usize>,
+    to: Option<usize>,
+    env: &Rt<Env>,
+    cx: &Context,
+) -> Result<usize> {
+    let Some(text) = string_text(string) else {
+        return Err(LispError::wrong_type(sym::STRINGP, string, cx).into());
+    };
+    let len = text.chars().count();
+    let from = from.unwrap_or(0);
+    let to = to.unwrap_or(len);
+    if !(from <= to && to <= len) {
+        let range = [string, (from as i64).into(), (to as i64).into()];
+        return Err(LispError::args_out_of_range(&range, cx).into());
+    }
+    let byte_offset = |pos| text.char_indices().nth(pos).map_or(text.len(), |(idx, _)| idx);
+    let text = &text[byte_offset(from)..byte_offset(to)];
+    Ok(display_width(text, text_has_raw_bytes(string), tab_width(env, cx)))
+}
+
+defvar!(TRUNCATE_STRING_ELLIPSIS);
+
+#[defun]
+#[expect(clippy::too_many_arguments)]
+fn truncate_string_to_width(
+    string: &str,
+    end_column: usize,
+    start_column: Option<usize>,
+    padding: Option<char>,
+    ellipsis: Option<Object>,
+    _ellipsis_text_property: OptionalFlag,
+    env: &Rt<Env>,
+    cx: &Context,
+) -> Result<String> {
+    let tab_width = tab_width(env, cx);
+    let start_column = start_column.unwrap_or(0);
+    let default_ellipsis = || {
+        let ellipsis = env.vars.get(sym::TRUNCATE_STRING_ELLIPSIS).map(|x| x.bind(cx));
+        ellipsis.and_then(string_text).unwrap_or(Cow::Borrowed("…"))
+    };
+    let ellipsis = match ellipsis {
+        None => Cow::Borrowed(""),
+        // any other non-nil value means to use the default ellipsis
+        Some(ellipsis) => string_text(ellipsis).unwrap_or_else(default_ellipsis),
+    };
+    let mut ellipsis = &*ellipsis;
+    let pad = |width: usize| padding.map_or_else(String::new, |chr| chr.to_string().repeat(width));
+
+    // a cluster is never split, so emoji sequences are kept whole
+    let clusters: Vec<&str> = string.graphemes(true).collect();
+    // A wide char that straddles the start column is skipped whole, so the
+    // result never starts in the middle of one. The columns it covers past
+    // the start become head padding.
+    let mut column = 0;
+    let mut idx = 0;
+    while column < start_column && idx < clusters.len() {
+        column = next_column(column, clusters[idx], false, tab_width);
+        idx += 1;
+    }
+    if column < start_column {
+        return Ok(pad(end_column));
+    }
+    let head_padding = pad(column - start_column);
+    let from = idx;
+    let mut end_column = end_column;
+    let mut tail_padding = String::new();
+    if end_column >= column {
+        // Only make room for the ellipsis if the string will be truncated
+        let string_width = display_width(string, false, tab_width);
+        let ellipsis_width = display_width(ellipsis, false, tab_width);
+        if end_column < string_width && string_width > ellipsis_width {
+            end_column = end_column.saturating_sub(ellipsis_width);
+        } else {
+            ellipsis = "";
+        }
+        let (mut last_column, mut last_idx) = (column, idx);
+        while column < end_column && idx < clusters.len() {
+            (last_column, last_idx) = (column, idx);
+            column = next_column(column, clusters[idx], false, tab_width);
+            idx += 1;
+        }
+        // don't split a wide char at the end column
+        if column > end_column {
+            (column, idx) = (last_column, last_idx);
+        }
+        tail_padding = pad(end_column.saturating_sub(column));
+    }
+    let mut truncated = head_padding;
+    truncated.extend(&clusters[from..idx]);
+    truncated += &tail_padding;
+    truncated += ellipsis;
+    Ok(truncated)
+}
+
+/// Pad `string` to `length` with `padding`, before the string if `start` is
+/// non-nil. Unlike [`truncate_string_to_width`], `length` counts characters
+/// rather than columns, so a wide character still only counts once.
+#[defun]
+fn string_pad<'ob>(
+    string: Object<'ob>,
+    length: Object,
+    padding: Option<char>,
+    start: OptionalFlag,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    let Ok(length) = usize::try_from(length) else {
+        return Err(LispError::wrong_type(sym::NATNUMP, length, cx).into());
+    };
+    let padding = padding.unwrap_or(' ');
+    let Some(codes) = string_codes(string) else {
+        return Err(TypeError::new(Type::String, string).into());
+    };
+    let len = codes.clone().count();
+    if length <= len {
+        return Ok(string);
+    }
+    // padding a unibyte string with ASCII keeps it unibyte
+    let multibyte = codes.is_multibyte() || !padding.is_ascii();
+    let mut codes: Vec<i64> =
+        if multibyte { codes.as_multibyte().collect() } else { codes.collect() };
+    let fill = std::iter::repeat_n(i64::from(u32::from(padding)), length - len);
+    if start.is_some() {
+        codes.splice(0..0, fill);
+    } else {
+        codes.extend(fill);
+    }
+    string_from_codes(codes.into_iter(), multibyte, cx)
+}
 
 #[defun]
 fn unibyte_string(bytes: &[Gc<i64>]) -> Result<Vec<u8>> {
@@ -13,39 +526,413 @@ fn unibyte_string(bytes: &[Gc<i64>]) -> Result<Vec<u8>> {
 }
 
 #[defun]
-fn max_char(unicode: OptionalFlag) -> usize {
-    if unicode.is_some() { std::char::MAX as usize } else { 0x3F_FFFF }
+fn unibyte_char_to_multibyte(ch: i64) -> Result<i64> {
+    match u8::try_from(ch) {
+        Ok(byte) => Ok(unibyte_to_code(byte)),
+        Err(_) => bail!("Not a unibyte character: {ch}"),
+    }
+}
+
+#[defun]
+fn multibyte_char_to_unibyte(ch: i64) -> i64 {
+    // -1 for characters that are neither ASCII nor eight-bit
+    match ch {
+        0..0x80 => ch,
+        _ if is_eight_bit(ch) => ch - EIGHT_BIT_BASE,
+        _ => -1,
+    }
+}
+
+#[defun]
+fn max_char(unicode: OptionalFlag) -> i64 {
+    if unicode.is_some() { MAX_UNICODE_CHAR } else { MAX_CHAR }
 }
 
 #[defun]
 fn characterp(obj: Object) -> bool {
-    char::try_from(obj).is_ok()
+    // Every code up to `max-char` is a character, even the ones that are not
+    // valid Unicode scalar values like surrogates
+    matches!(obj.untag(), ObjectType::Int(0..=MAX_CHAR))
 }
 
 #[defun]
-fn string(characters: &[Gc<i64>]) -> Result<String> {
-    let string: Result<_, _> = characters.iter().map(|x| int_to_char(x.untag())).collect();
-    Ok(string?)
+fn string<'ob>(characters: &[Gc<i64>], cx: &'ob Context) -> Result<Object<'ob>> {
+    for chr in characters {
+        if code_to_char(chr.untag()).is_none() && !is_eight_bit(chr.untag()) {
+            return Err(TypeError::new(Type::Char, Object::from(*chr)).into());
+        }
+    }
+    // Like Emacs, only non-ASCII characters make the string multibyte
+    let multibyte = characters.iter().any(|x| !(0..0x80).contains(&x.untag()));
+    string_from_codes(characters.iter().map(|x| x.untag()), multibyte, cx)
 }
 
 #[defun]
 fn make_string<'ob>(
     length: usize,
     init: usize,
-    multibyte: OptionalFlag,
+    multibyte: Supplied<'ob>,
     cx: &'ob Context,
 ) -> Result<Object<'ob>> {
-    if multibyte.is_some() {
-        let chr = int_to_char(i64::try_from(init)?)?;
-        let size = chr.len_utf8();
-        let mut string = cx.string_with_capacity(length * size);
+    // Without the flag, a non-ASCII init is a multibyte char. An explicit nil
+    // makes an init up to 255 a raw byte instead.
+    let multibyte = match multibyte {
+        None => init > 0x7F,
+        Some(flag) => !flag.is_nil() || init > 0xFF,
+    };
+    if multibyte {
+        let code = i64::try_from(init)?;
+        let eight_bit = is_eight_bit(code);
+        let chr = match code_to_char(code) {
+            Some(chr) => chr,
+            None if eight_bit => raw_byte_to_char((code - EIGHT_BIT_BASE) as u8),
+            None => return Err(TypeError::new(Type::Char, cx.add(code)).into()),
+        };
+        // string capacity is in bytes, not chars
+        let size = length.saturating_mul(chr.len_utf8());
+        check_array_size(size)?;
+        let mut string = String::with_capacity(size);
         for _ in 0..length {
             string.push(chr);
         }
-        Ok(cx.add(string))
+        debug_assert_eq!(string.len(), size);
+        // an eight-bit init makes a string of raw bytes
+        if eight_bit { Ok(cx.add(RawBytesText(string))) } else { Ok(cx.add(string)) }
     } else {
         let chr = u8::try_from(init)?;
+        check_array_size(length)?;
         let string: Vec<_> = (0..length).map(|_| chr).collect();
         Ok(cx.add(string))
     }
 }
+
+/// Return the character that represents `weight` in `radix` (default 10). This
+/// is the inverse of `cl-digit-char-p`.
+#[defun]
+fn cl_digit_char(weight: i64, radix: Option<i64>) -> Result<Option<char>> {
+    let radix = radix.unwrap_or(10);
+    ensure!((2..=36).contains(&radix), "Invalid radix: {radix}");
+    let Ok(weight) = u32::try_from(weight) else { return Ok(None) };
+    Ok(char::from_digit(weight, radix as u32))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interpreter::{assert_lisp, assert_lisp_signal};
+
+    #[test]
+    fn test_eight_bit_chars() {
+        assert_lisp("(unibyte-char-to-multibyte 97)", "97");
+        assert_lisp("(unibyte-char-to-multibyte 255)", "4194303");
+        assert_lisp("(unibyte-char-to-multibyte 128)", "4194176");
+        assert_lisp("(condition-case nil (unibyte-char-to-multibyte 256) (error 'err))", "err");
+        assert_lisp("(multibyte-char-to-unibyte 4194303)", "255");
+        assert_lisp("(multibyte-char-to-unibyte 97)", "97");
+        assert_lisp("(multibyte-char-to-unibyte ?λ)", "-1");
+        // reading an eight-bit character gives back its code, not the raw byte
+        let eight_bit = "(string ?a (unibyte-char-to-multibyte 200) ?λ)";
+        assert_lisp(&format!("(aref {eight_bit} 1)"), "4194248");
+        assert_lisp(&format!("(elt {eight_bit} 1)"), "4194248");
+        assert_lisp(&format!("(string-to-char (substring {eight_bit} 1))"), "4194248");
+        assert_lisp(&format!("(append {eight_bit} nil)"), "(97 4194248 955)");
+        assert_lisp("(aref (concat \"λ\" (unibyte-string 255)) 1)", "4194303");
+        assert_lisp("(characterp (aref (concat \"λ\" (unibyte-string 255)) 1))", "t");
+        // the raw byte itself is only returned from a unibyte string
+        assert_lisp("(aref (unibyte-string 255) 0)", "255");
+        assert_lisp("(string-to-char (unibyte-string 255))", "255");
+        assert_lisp("(string-to-char \"\")", "0");
+        let round_trip = "(string (unibyte-char-to-multibyte 255))";
+        assert_lisp(
+            &format!(
+                "(equal {round_trip} (concat \"\" (string-to-multibyte (unibyte-string 255))))"
+            ),
+            "t",
+        );
+    }
+
+    #[test]
+    fn test_raw_bytes_out_of_band() {
+        // the chars that stand in for raw bytes are ordinary chars in a string
+        // without raw bytes
+        assert_lisp("(aref (string #x10FFFF) 0)", "1114111");
+        assert_lisp("(aref (string #x10FF80 ?λ) 0)", "1113984");
+        assert_lisp("(aref (make-string 2 #x10FFC8) 1)", "1114056");
+        assert_lisp("(aref (concat '(#x10FFC8)) 0)", "1114056");
+        assert_lisp("(aref (char-to-string #x10FFFF) 0)", "1114111");
+        assert_lisp("(string-bytes (string #x10FFFF))", "4");
+        // while a raw byte with the same low byte is still eight-bit
+        assert_lisp("(aref (make-string 2 (unibyte-char-to-multibyte 255)) 1)", "4194303");
+        assert_lisp("(string-bytes (string (unibyte-char-to-multibyte 255)))", "2");
+        let (real, raw) = ("(string #x10FFFF)", "(string (unibyte-char-to-multibyte 255))");
+        assert_lisp(&format!("(equal {real} {raw})"), "nil");
+        assert_lisp(&format!("(string= {real} (unibyte-string 255))"), "nil");
+        // raw bytes stay raw bytes in the strings made from them
+        let multi = r#"(concat "λ" (unibyte-string 255))"#;
+        assert_lisp(multi, r#""λ\377""#);
+        assert_lisp(&format!("(aref (copy-sequence {multi}) 1)"), "4194303");
+        assert_lisp(&format!("(aref (upcase {multi}) 1)"), "4194303");
+        assert_lisp(&format!("(aref (format \"%s\" {multi}) 1)"), "4194303");
+        assert_lisp("(aref (format \"%c\" (unibyte-char-to-multibyte 255)) 0)", "4194303");
+        assert_lisp(&format!("(aref (mapconcat #'identity (list {multi}) \"\") 1)"), "4194303");
+        let encoded = "(encode-coding-string (string #x10FFFF) 'utf-8)";
+        assert_lisp(&format!("(append {encoded} nil)"), "(244 143 191 191)");
+        assert_lisp(&format!("(aref (decode-coding-string {encoded} 'utf-8) 0)"), "1114111");
+        // so one string can't hold both
+        let mixed = format!("(error \"{MIXED_RAW_BYTES}\")");
+        assert_lisp_signal("(string #x10FFFF (unibyte-char-to-multibyte 255))", &mixed);
+        assert_lisp_signal(&format!("(concat {real} (unibyte-string 255))"), &mixed);
+        assert_eq!(code_to_char(MAX_UNICODE_CHAR), Some('\u{10FFFF}'));
+        assert_eq!(code_to_char(MAX_CHAR), None);
+        assert_eq!(char_code(raw_byte_to_char(0x80), true), EIGHT_BIT_BASE + 0x80);
+        assert_eq!(char_code(raw_byte_to_char(0x80), false), 0x10_FF80);
+    }
+
+    #[test]
+    fn test_max_char() {
+        assert_lisp("(max-char)", "4194303");
+        assert_lisp("(max-char t)", "1114111");
+        assert_lisp("(characterp (max-char))", "t");
+        assert_lisp("(characterp (1+ (max-char)))", "nil");
+        assert_lisp("(characterp (max-char t))", "t");
+        assert_lisp("(characterp 0)", "t");
+        assert_lisp("(characterp -1)", "nil");
+        assert_lisp("(characterp 55296)", "t");
+        assert_lisp("(characterp 1.0)", "nil");
+    }
+
+    #[test]
+    fn test_string_multibyte() {
+        assert_lisp("(multibyte-string-p (string 97 98))", "nil");
+        assert_lisp("(multibyte-string-p (string))", "nil");
+        assert_lisp("(multibyte-string-p (string 97 955))", "t");
+        assert_lisp("(string-bytes (string 97 98))", "2");
+        assert_lisp("(string-bytes (string 97 955))", "3");
+        // unibyte ASCII strings still work like any other string
+        assert_lisp("(equal (string 97 98) \"ab\")", "t");
+        assert_lisp("(equal (string 97 98) (string 97 99))", "nil");
+        assert_lisp("(substring (string 97 98 99) 1)", "\"bc\"");
+        assert_lisp("(string-to-number (string 52 50))", "42");
+        assert_lisp("(stringp (string 97))", "t");
+        assert_lisp("(stringp (char-to-string 97))", "t");
+        assert_lisp("(intern-soft (string 99 97 114))", "car");
+        assert_lisp("(buffer-name (get-buffer-create (string 97 98)))", "\"ab\"");
+        assert_lisp("(let ((s (string 97 98))) (clear-string s) (equal s (string 0 0)))", "t");
+    }
+
+    #[test]
+    fn test_nul_chars() {
+        assert_lisp("(length (char-to-string 0))", "1");
+        assert_lisp("(aref (char-to-string 0) 0)", "0");
+        assert_lisp("(length (string 97 0 98))", "3");
+        assert_lisp("(aref (string 97 0 98) 1)", "0");
+        assert_lisp("(length (make-string 3 0))", "3");
+        assert_lisp("(aref (make-string 3 0 t) 2)", "0");
+        assert_lisp("(length (concat \"a\" (string 0) \"b\"))", "3");
+        assert_lisp("(equal (concat (string 0) (string 0)) (make-string 2 0 t))", "t");
+        assert_lisp("(string-equal (string 0) (string 0 0))", "nil");
+    }
+
+    #[test]
+    fn test_make_string() {
+        assert_lisp("(make-string 3 ?a)", "\"aaa\"");
+        assert_lisp("(multibyte-string-p (make-string 3 ?a))", "nil");
+        assert_lisp("(multibyte-string-p (make-string 3 ?a t))", "t");
+        assert_lisp("(make-string 2 ?λ)", "\"λλ\"");
+        assert_lisp("(length (make-string 1000 ?λ))", "1000");
+        assert_lisp("(string-bytes (make-string 1000 ?λ))", "2000");
+        assert_lisp("(string-bytes (make-string 1000 ?😀))", "4000");
+    }
+
+    #[test]
+    fn test_make_string_explicit_unibyte() {
+        // an explicit nil flag keeps a byte init as a raw byte
+        assert_lisp("(multibyte-string-p (make-string 2 200 nil))", "nil");
+        assert_lisp("(append (make-string 2 200 nil) nil)", "(200 200)");
+        assert_lisp("(string-bytes (make-string 2 200 nil))", "2");
+        assert_lisp("(append (make-string 1 255 nil) nil)", "(255)");
+        assert_lisp("(multibyte-string-p (make-string 2 ?a nil))", "nil");
+        // but without the flag it is the character with that code
+        assert_lisp("(multibyte-string-p (make-string 2 200))", "t");
+        assert_lisp("(make-string 2 200)", "\"ÈÈ\"");
+        assert_lisp("(string-bytes (make-string 2 200))", "4");
+        assert_lisp("(multibyte-string-p (make-string 2 200 t))", "t");
+        // a char that is not a byte is multibyte either way
+        assert_lisp("(make-string 2 ?λ nil)", "\"λλ\"");
+        assert_lisp("(multibyte-string-p (make-string 2 256 nil))", "t");
+    }
+
+    #[test]
+    fn test_string_width() {
+        assert_lisp("(string-width \"\")", "0");
+        assert_lisp("(string-width \"abc\")", "3");
+        assert_lisp("(string-width \"日本\")", "4");
+        assert_lisp("(string-width \"e\u{301}\")", "1");
+        assert_lisp("(string-width \"abcdef\" 1 3)", "2");
+        assert_lisp_signal("(string-width \"abc\" 1 4)", "(args-out-of-range \"abc\" 1 4)");
+        assert_lisp_signal("(string-width \"abc\" 2 1)", "(args-out-of-range \"abc\" 2 1)");
+        assert_lisp("(char-width ?日)", "2");
+        assert_lisp("(char-width 1)", "2");
+    }
+
+    #[test]
+    fn test_control_char_width() {
+        assert_lisp("(char-width 10)", "0");
+        assert_lisp("(char-width 0)", "2");
+        assert_lisp("(char-width 27)", "2");
+        assert_lisp("(char-width 127)", "2");
+        assert_lisp("(char-width #x85)", "4");
+        assert_lisp("(string-width \"a\x01b\nc\")", "5");
+        assert_lisp("(string-width \"\n\n\")", "0");
+        assert_lisp("(string-width \"\r\n\")", "2");
+        assert_lisp("(string-width (string ?a 127 #x9f))", "7");
+        // raw bytes are displayed as octal escapes
+        assert_lisp("(string-width (string ?a #x3fffff))", "5");
+        assert_lisp("(string-width (unibyte-string ?a 255 ?b))", "6");
+        assert_lisp("(string-width (concat \"日\" (unibyte-string 200)) 1)", "4");
+        assert_lisp("(string-width \"\u{10FFFF}\")", "1");
+    }
+
+    #[test]
+    fn test_require_character() {
+        assert_lisp_signal("(char-to-string -1)", "(wrong-type-argument characterp -1)");
+        assert_lisp_signal("(char-to-string 1.5)", "(wrong-type-argument characterp 1.5)");
+        assert_lisp_signal("(char-to-string \"a\")", "(wrong-type-argument characterp \"a\")");
+        assert_lisp_signal(
+            "(char-to-string (1+ (max-char)))",
+            "(wrong-type-argument characterp 4194304)",
+        );
+        assert_lisp_signal("(char-equal ?a 'b)", "(wrong-type-argument characterp b)");
+        assert_lisp_signal("(char-equal -5 ?a)", "(wrong-type-argument characterp -5)");
+        assert_lisp_signal("(char-width 2.0)", "(wrong-type-argument characterp 2.0)");
+        assert_lisp_signal("(char-width nil)", "(wrong-type-argument characterp nil)");
+        // every code up to `max-char` is a character
+        assert_lisp("(char-width (max-char))", "4");
+        assert_lisp("(char-width #xD800)", "1");
+        assert_lisp("(char-equal #xD800 #xD800)", "t");
+    }
+
+    #[test]
+    fn test_string_width_emoji() {
+        // a flag is a pair of regional indicators
+        assert_lisp("(string-width \"\u{1F1EF}\u{1F1F5}\")", "2");
+        assert_lisp("(string-width \"\u{1F1EF}\u{1F1F5}\u{1F1EB}\u{1F1F7}\")", "4");
+        assert_lisp("(length \"\u{1F1EF}\u{1F1F5}\")", "2");
+        // the family emoji is three emoji joined by ZWJs
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_lisp(&format!("(string-width \"{family}\")"), "2");
+        assert_lisp(&format!("(string-width \"a{family}b\")"), "4");
+        assert_lisp("(string-width \"\u{2764}\u{FE0F}\u{200D}\u{1F525}\")", "2");
+        // variation selectors take no columns
+        assert_lisp("(string-width \"\u{263A}\u{FE0F}\")", "1");
+        assert_lisp("(string-width \"\u{1F600}\u{FE0F}\")", "2");
+        assert_lisp("(string-width \"x\u{FE0E}\")", "1");
+        // clusters are measured whole, but FROM and TO are still chars
+        assert_lisp(&format!("(string-width \"{family}\" 0 1)"), "2");
+        assert_lisp(&format!("(string-width \"{family}\" 2 5)"), "2");
+        // a ZWJ that doesn't join emoji is just a zero width char
+        assert_lisp("(string-width \"a\u{200D}b\")", "2");
+        assert_lisp(
+            &format!("(truncate-string-to-width \"{family}ab\" 3)"),
+            &format!("\"{family}a\""),
+        );
+        assert_lisp(&format!("(truncate-string-to-width \"{family}ab\" 1)"), "\"\"");
+        assert_lisp(
+            "(truncate-string-to-width \"\u{1F1EF}\u{1F1F5}x\" 2)",
+            "\"\u{1F1EF}\u{1F1F5}\"",
+        );
+    }
+
+    #[test]
+    fn test_string_width_tabs() {
+        // tab stops are relative to the start of the string
+        assert_lisp("(string-width \"\t\")", "8");
+        assert_lisp("(string-width \"ab\t\")", "8");
+        assert_lisp("(string-width \"ab\tc\")", "9");
+        assert_lisp("(string-width \"abcdefgh\t\")", "16");
+        assert_lisp("(string-width \"日本\tx\")", "9");
+        assert_lisp("(let ((tab-width 4)) (string-width \"a\tb\"))", "5");
+        assert_lisp("(let ((tab-width 4)) (string-width \"abcd\t\"))", "8");
+        assert_lisp("(let ((tab-width 4)) (char-width 9))", "4");
+    }
+
+    #[test]
+    fn test_truncate_string_to_width() {
+        assert_lisp("(truncate-string-to-width \"abcdef\" 4)", "\"abcd\"");
+        assert_lisp("(truncate-string-to-width \"abcdef\" 10)", "\"abcdef\"");
+        assert_lisp("(truncate-string-to-width \"abcdef\" 4 2)", "\"cd\"");
+        assert_lisp("(truncate-string-to-width \"ab\" 4 nil ?x)", "\"abxx\"");
+        assert_lisp("(truncate-string-to-width \"日本語\" 5)", "\"日本\"");
+        assert_lisp("(truncate-string-to-width \"日本語\" 5 nil ?x)", "\"日本x\"");
+    }
+
+    #[test]
+    fn test_truncate_string_start_column() {
+        // starting on a char boundary
+        assert_lisp("(truncate-string-to-width \"日本語\" 6 2)", "\"本語\"");
+        // starting inside 日 skips it, and pads its second column if asked to
+        assert_lisp("(truncate-string-to-width \"日本語\" 6 1)", "\"本語\"");
+        assert_lisp("(truncate-string-to-width \"日本語\" 6 1 ?x)", "\"x本語\"");
+        assert_lisp("(string-width (truncate-string-to-width \"日本語\" 6 1 ?x))", "5");
+        assert_lisp("(truncate-string-to-width \"a日b\" 4 2)", "\"b\"");
+        assert_lisp("(truncate-string-to-width \"a日b\" 4 2 ?x)", "\"xb\"");
+        // and a wide char can't end past the end column either
+        assert_lisp("(truncate-string-to-width \"日本語\" 5 3 ?x)", "\"xx\"");
+        // starting past the end of the string gives only padding
+        assert_lisp("(truncate-string-to-width \"日本\" 6 5)", "\"\"");
+        assert_lisp("(truncate-string-to-width \"日本\" 6 5 ?x)", "\"xxxxxx\"");
+    }
+
+    #[test]
+    fn test_string_pad() {
+        assert_lisp("(string-pad \"abc\" 5)", "\"abc  \"");
+        assert_lisp("(string-pad \"abc\" 5 ?-)", "\"abc--\"");
+        assert_lisp("(string-pad \"abc\" 5 ?- t)", "\"--abc\"");
+        assert_lisp("(string-pad \"abcdef\" 3)", "\"abcdef\"");
+        assert_lisp("(string-pad \"\" 2 ?λ)", "\"λλ\"");
+        assert_lisp("(multibyte-string-p (string-pad (string 97) 3))", "nil");
+        assert_lisp_signal("(string-pad \"a\" -1)", "(wrong-type-argument natnump -1)");
+    }
+
+    #[test]
+    fn test_string_pad_wide_chars() {
+        // string-pad counts characters, but truncate-string-to-width counts
+        // columns, so they disagree about wide characters
+        assert_lisp("(string-pad \"日本\" 5)", "\"日本   \"");
+        assert_lisp("(length (string-pad \"日本\" 5))", "5");
+        assert_lisp("(string-width (string-pad \"日本\" 5))", "7");
+        assert_lisp("(string-pad \"日本語\" 3)", "\"日本語\"");
+        assert_lisp("(truncate-string-to-width \"日本\" 5 nil ?\\s)", "\"日本 \"");
+        assert_lisp("(string-width (truncate-string-to-width \"日本\" 5 nil ?\\s))", "5");
+    }
+
+    #[test]
+    fn test_truncate_string_ellipsis() {
+        // nil means no ellipsis
+        assert_lisp("(truncate-string-to-width \"abcdef\" 4 nil nil nil)", "\"abcd\"");
+        // t uses `truncate-string-ellipsis' or the default
+        assert_lisp("(truncate-string-to-width \"abcdef\" 4 nil nil t)", "\"abc…\"");
+        assert_lisp(
+            "(let ((truncate-string-ellipsis \"~\")) (truncate-string-to-width \"abcdef\" 4 nil nil t))",
+            "\"abc~\"",
+        );
+        // a string is used as is
+        assert_lisp("(truncate-string-to-width \"abcdef\" 4 nil nil \"..\")", "\"ab..\"");
+        assert_lisp("(truncate-string-to-width \"abcdef\" 4 nil nil (string ?~))", "\"abc~\"");
+        // no ellipsis when nothing is truncated
+        assert_lisp("(truncate-string-to-width \"abcd\" 4 nil nil t)", "\"abcd\"");
+        assert_lisp("(truncate-string-to-width \"abcd\" 4 nil nil \"..\")", "\"abcd\"");
+        assert_lisp("(truncate-string-to-width \"日本語\" 5 nil nil t)", "\"日本…\"");
+        assert_lisp("(truncate-string-to-width \"日本語\" 4 nil nil t)", "\"日…\"");
+    }
+
+    #[test]
+    fn test_cl_digit_char() {
+        assert_lisp("(cl-digit-char 7)", "?7");
+        assert_lisp("(cl-digit-char 10 16)", "?a");
+        assert_lisp("(cl-digit-char 35 36)", "?z");
+        assert_lisp("(cl-digit-char 10)", "nil");
+        assert_lisp("(cl-digit-char 2 2)", "nil");
+        assert_lisp("(cl-digit-char -1)", "nil");
+    }
+}
@@ -0,0 +1,190 @@
+//! Coding system conversions.
+use crate::{
+    character::{EIGHT_BIT_BASE, MultibyteText, char_to_raw_byte},
+    core::{
+        error::{Type, TypeError},
+        gc::Context,
+        object::{LispString, Object, ObjectType, OptionalFlag},
+    },
+    data::LispError,
+};
+use anyhow::{Result, bail};
+use rune_macros::defun;
+
+/// The coding systems that can be decoded and encoded. Each one also accepts its `-unix`
+/// variant, since no end of line conversion is done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Coding {
+    Utf8,
+    Latin1,
+    Binary,
+}
+
+impl Coding {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.strip_suffix("-unix").unwrap_or(name) {
+            "utf-8" | "utf-8-emacs" | "prefer-utf-8" | "mule-utf-8" => Some(Self::Utf8),
+            "latin-1" | "iso-latin-1" | "iso-8859-1" => Some(Self::Latin1),
+            "binary" | "no-conversion" | "raw-text" => Some(Self::Binary),
+            _ => None,
+        }
+    }
+
+    /// The coding system named by `coding_system`, or `None` for nil.
+    fn from_obj(coding_system: Object, cx: &Context) -> Result<Option<Self>> {
+        match coding_system.untag() {
+            ObjectType::NIL => Ok(None),
+            ObjectType::Symbol(sym) => match Self::from_name(sym.name()) {
+                Some(coding) => Ok(Some(coding)),
+                None => Err(LispError::coding_system_error(coding_system, cx).into()),
+            },
+            _ => Err(TypeError::new(Type::Symbol, coding_system).into()),
+        }
+    }
+}
+
+/// The raw byte `chr` stands for, if it is one in `string`.
+fn raw_byte(string: &LispString, chr: char) -> Option<u8> {
+    char_to_raw_byte(chr).filter(|_| string.has_raw_bytes())
+}
+
+/// The bytes of `string`. Multibyte strings are taken in their UTF-8 form,
+/// except for eight-bit characters which are the raw byte they stand for.
+fn string_bytes(string: Object) -> Result<Vec<u8>> {
+    match string.untag() {
+        ObjectType::ByteString(bytes) => Ok(bytes.to_vec()),
+        ObjectType::String(string) => {
+            let mut bytes = Vec::with_capacity(string.len());
+            for chr in string.chars() {
+                match raw_byte(string, chr) {
+                    Some(byte) => bytes.push(byte),
+                    None => bytes.extend_from_slice(chr.encode_utf8(&mut [0; 4]).as_bytes()),
+                }
+            }
+            Ok(bytes)
+        }
+        _ => Err(TypeError::new(Type::String, string).into()),
+    }
+}
+
+/// Decode UTF-8 `bytes`. Bytes that are not part of a valid sequence become
+/// eight-bit characters, so the original bytes can be recovered.
+fn decode_utf8(bytes: &[u8]) -> Result<MultibyteText> {
+    let mut decoded = MultibyteText::default();
+    for chunk in bytes.utf8_chunks() {
+        decoded.push_str(chunk.valid(), false)?;
+        for &byte in chunk.invalid() {
+            decoded.push_code(EIGHT_BIT_BASE + i64::from(byte))?;
+        }
+    }
+    Ok(decoded)
+}
+
+#[defun]
+fn decode_coding_string<'ob>(
+    string: Object<'ob>,
+    coding_system: Object,
+    _nocopy: OptionalFlag,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    // nil means no decoding at all
+    let Some(coding) = Coding::from_obj(coding_system, cx)? else { return Ok(string) };
+    let bytes = string_bytes(string)?;
+    Ok(match coding {
+        Coding::Utf8 => cx.add(decode_utf8(&bytes)?),
+        Coding::Latin1 => cx.add(bytes.iter().map(|&b| char::from(b)).collect::<String>()),
+        Coding::Binary => cx.add(bytes),
+    })
+}
+
+/// Encode `string` as Latin-1. Eight-bit characters are their raw byte, and
+/// anything outside of Latin-1 is an error.
+fn encode_latin1(string: &LispString) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(string.len());
+    for chr in string.chars() {
+        match raw_byte(string, chr).or_else(|| u8::try_from(chr).ok()) {
+            Some(byte) => bytes.push(byte),
+            None => bail!("Cannot encode {chr:?} with latin-1"),
+        }
+    }
+    Ok(bytes)
+}
+
+#[defun]
+fn encode_coding_string<'ob>(
+    string: Object<'ob>,
+    coding_system: Object,
+    _nocopy: OptionalFlag,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    let Some(coding) = Coding::from_obj(coding_system, cx)? else { return Ok(string) };
+    let bytes = match (coding, string.untag()) {
+        // unibyte strings are already encoded
+        (Coding::Latin1, ObjectType::String(string)) => encode_latin1(string)?,
+        _ => string_bytes(string)?,
+    };
+    Ok(cx.add(bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::interpreter::{assert_lisp, assert_lisp_signal};
+
+    #[test]
+    fn test_decode_utf8() {
+        assert_lisp("(decode-coding-string (unibyte-string 226 130 172) 'utf-8)", "\"€\"");
+        assert_lisp("(decode-coding-string \"abc\" 'utf-8-unix)", "\"abc\"");
+        assert_lisp("(multibyte-string-p (decode-coding-string \"abc\" 'utf-8))", "t");
+        assert_lisp("(decode-coding-string \"λx\" 'utf-8)", "\"λx\"");
+        assert_lisp("(multibyte-string-p (decode-coding-string (unibyte-string 206) nil))", "nil");
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8() {
+        // invalid bytes become eight-bit characters. The expected strings
+        // start with a multibyte "λ" so concat upgrades the bytes the same way.
+        let decode = |bytes| format!("(decode-coding-string (unibyte-string {bytes}) 'utf-8)");
+        let equal = |bytes, expect| format!("(equal {} (concat \"λ\" {expect}))", decode(bytes));
+        assert_lisp(&equal("206 187 97 255 98", r#""a" (unibyte-string 255) "b""#), "t");
+        // a truncated sequence keeps every byte
+        assert_lisp(&equal("206 187 226 130", "(unibyte-string 226 130)"), "t");
+        assert_lisp(&equal("206 187 226 130 97", r#"(unibyte-string 226 130) "a""#), "t");
+        assert_lisp(&format!("(length {})", decode("255 206 187 128")), "3");
+        // eight-bit characters decode as the bytes they stand for
+        let raw = r#"(concat "λ" (unibyte-string 226 130 172))"#;
+        assert_lisp(&format!("(length {raw})"), "4");
+        assert_lisp(&format!("(decode-coding-string {raw} 'utf-8)"), "\"λ€\"");
+    }
+
+    #[test]
+    fn test_decode_latin1() {
+        assert_lisp("(decode-coding-string (unibyte-string 233 97) 'latin-1)", "\"éa\"");
+        assert_lisp("(decode-coding-string (unibyte-string 255) 'iso-8859-1)", "\"ÿ\"");
+        assert_lisp_signal("(decode-coding-string \"a\" 'foo)", "(coding-system-error foo)");
+        assert_lisp_signal("(encode-coding-string \"a\" 'foo)", "(coding-system-error foo)");
+    }
+
+    #[test]
+    fn test_encode_utf8() {
+        let bytes = |form| format!("(append (encode-coding-string {form} 'utf-8) nil)");
+        assert_lisp(&bytes("\"€a\""), "(226 130 172 97)");
+        assert_lisp("(multibyte-string-p (encode-coding-string \"abc\" 'utf-8))", "nil");
+        assert_lisp("(encode-coding-string \"λ\" nil)", "\"λ\"");
+        // eight-bit characters encode back to their raw byte
+        assert_lisp(&bytes(r#"(concat "λ" (unibyte-string 255))"#), "(206 187 255)");
+        let round_trip = "(decode-coding-string (encode-coding-string \"a€λ\" 'utf-8) 'utf-8)";
+        assert_lisp(round_trip, "\"a€λ\"");
+    }
+
+    #[test]
+    fn test_encode_latin1() {
+        let bytes = |form| format!("(append (encode-coding-string {form} 'latin-1) nil)");
+        assert_lisp(&bytes("\"éaÿ\""), "(233 97 255)");
+        assert_lisp(&bytes(r#"(concat "é" (unibyte-string 200))"#), "(233 200)");
+        assert_lisp(&bytes("(unibyte-string 128 97)"), "(128 97)");
+        assert_lisp_signal(
+            "(encode-coding-string \"a€\" 'iso-8859-1)",
+            "(error \"Cannot encode '€' with latin-1\")",
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use super::{Gc, Object, ObjectType, TagType, WithLifetime};
 use crate::{
+    character::{code_to_char, is_eight_bit},
     core::{
         error::{Type, TypeError},
         gc::{Block, Context, GcHeap, GcState, Trace},
@@ -45,14 +46,22 @@ impl OpenBuffer<'_> {
         cx.bind(self.back_ref)
     }
 
+    /// Insert a string or character at point. Buffer text can't hold raw
+    /// bytes, since nothing marks which chars of it stand in for them.
     pub(crate) fn insert(&mut self, arg: Object) -> Result<()> {
+        const RAW_BYTES: &str = "Raw bytes can't be inserted into a buffer";
         match arg.untag() {
+            ObjectType::Int(i) if is_eight_bit(i) => bail!(RAW_BYTES),
             ObjectType::Int(i) => {
-                let Ok(u_32) = i.try_into() else { bail!("{i} is an invalid char") };
-                let Some(chr) = char::from_u32(u_32) else { bail!("{i} is an Invalid char") };
+                let Some(chr) = code_to_char(i) else { bail!("{i} is an invalid char") };
                 self.get_mut().text.insert_char(chr);
             }
+            ObjectType::String(s) if s.has_raw_bytes() => bail!(RAW_BYTES),
             ObjectType::String(s) => self.get_mut().text.insert(s),
+            ObjectType::ByteString(s) if s.is_ascii() => {
+                self.get_mut().text.insert(std::str::from_utf8(s).unwrap());
+            }
+            ObjectType::ByteString(_) => bail!(RAW_BYTES),
             x => bail!(TypeError::new(Type::String, x)),
         }
         Ok(())
@@ -2,7 +2,7 @@
 //! this code could be replaced with macros or specialized generics if
 //! those are ever stabalized.
 
-use crate::data::LispError;
+use crate::{character::code_to_char, data::LispError};
 
 use super::{
     super::error::{Type, TypeError},
@@ -11,11 +11,19 @@ use super::{
 use super::{Gc, LispFloat, Object, ObjectType, Symbol};
 use anyhow::Context;
 
+/// An all-ASCII unibyte string has the same text as a multibyte one, so it
+/// can be used anywhere a `&str` is expected.
+fn ascii_str(string: &ByteString) -> &str {
+    debug_assert!(string.is_ascii());
+    std::str::from_utf8(string).unwrap()
+}
+
 impl<'ob> TryFrom<Object<'ob>> for &'ob str {
     type Error = anyhow::Error;
     fn try_from(obj: Object<'ob>) -> Result<Self, Self::Error> {
         match obj.untag() {
             ObjectType::String(x) => Ok(x),
+            ObjectType::ByteString(x) if x.is_ascii() => Ok(ascii_str(x)),
             x => Err(TypeError::new(Type::String, x).into()),
         }
     }
@@ -38,6 +46,7 @@ impl<'ob> TryFrom<Object<'ob>> for Option<&'ob str> {
         match obj.untag() {
             ObjectType::NIL => Ok(None),
             ObjectType::String(x) => Ok(Some(x)),
+            ObjectType::ByteString(x) if x.is_ascii() => Ok(Some(ascii_str(x))),
             x => Err(TypeError::new(Type::String, x)),
         }
     }
@@ -60,8 +69,7 @@ impl<'ob> TryFrom<Object<'ob>> for char {
     fn try_from(obj: Object<'ob>) -> Result<Self, Self::Error> {
         let err = || TypeError::new(Type::Char, obj);
         let ObjectType::Int(x) = obj.untag() else { Err(err())? };
-        let Ok(x) = u32::try_from(x) else { Err(err())? };
-        std::char::from_u32(x).ok_or_else(err)
+        code_to_char(x).ok_or_else(err)
     }
 }
 
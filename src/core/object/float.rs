@@ -39,14 +39,23 @@ impl<'new> CloneIn<'new, &'new LispFloat> for LispFloat {
     }
 }
 
+/// Write `float` the way Emacs prints it, where integral values still end in
+/// `.0`. This does not need the float to be allocated, so it can be used
+/// anywhere a number is formatted.
+pub(crate) fn write_float(f: &mut impl std::fmt::Write, float: f64) -> std::fmt::Result {
+    // Like Emacs, this ignores LC_NUMERIC, so the decimal point is always `.`
+    // Rust keeps the sign of negative zero, so -0.0 prints as "-0.0" like
+    // Emacs
+    if float.fract() == 0.0_f64 {
+        write!(f, "{float:.1}")
+    } else {
+        write!(f, "{float}")
+    }
+}
+
 impl Display for LispFloat {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let float = **self;
-        if float.fract() == 0.0_f64 {
-            write!(f, "{float:.1}")
-        } else {
-            write!(f, "{float}")
-        }
+        write_float(f, **self)
     }
 }
 
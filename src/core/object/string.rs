@@ -1,5 +1,7 @@
 use super::{CloneIn, IntoObject};
+use crate::character::char_to_raw_byte;
 use crate::core::gc::{AllocState, Block, GcHeap, GcMoveable, GcState, Trace};
+use anyhow::{Result, ensure};
 use std::cell::Cell;
 use std::fmt::{Debug, Display};
 use std::ops::Deref;
@@ -8,6 +10,11 @@ use std::ptr::NonNull;
 pub(crate) type GcString<'a> = bumpalo::collections::String<'a>;
 pub(crate) struct LispString(GcHeap<LispStringInner>);
 
+/// The text of a multibyte string that holds raw bytes. In it the chars
+/// U+10FF80 to U+10FFFF stand for eight-bit characters, see
+/// [`crate::character`].
+pub(crate) struct RawBytesText(pub(crate) String);
+
 // This type needs to be this complex due to to string mutation.
 //
 // Case 1: The new char is the same utf8 size as the old one:
@@ -15,7 +22,10 @@ pub(crate) struct LispString(GcHeap<LispStringInner>);
 //
 // Case 2: The new char is a different size:
 // Need to allocate a new string and update the cell to point to that.
-struct LispStringInner(Cell<*mut str>);
+struct LispStringInner {
+    text: Cell<*mut str>,
+    raw_bytes: Cell<bool>,
+}
 
 impl GcMoveable for LispString {
     type Value = std::ptr::NonNull<LispString>;
@@ -27,7 +37,8 @@ impl GcMoveable for LispString {
             AllocState::Unmoved => {
                 let ptr = {
                     let mut new = GcString::from_str_in(self, to_space);
-                    let lisp_str = unsafe { LispString::new(new.as_mut_str(), false) };
+                    let lisp_str =
+                        unsafe { LispString::new(new.as_mut_str(), self.has_raw_bytes(), false) };
                     std::mem::forget(new);
                     let alloc = to_space.alloc(lisp_str);
                     NonNull::from(alloc)
@@ -56,7 +67,11 @@ impl Display for LispString {
             match c {
                 '\\' => output.push_str("\\\\"),
                 '"' => output.push_str("\\\""),
-                c => output.push(c),
+                c => match char_to_raw_byte(c) {
+                    // raw bytes are printed like in a unibyte string
+                    Some(byte) if self.has_raw_bytes() => output.push_str(&format!("\\{byte:03o}")),
+                    _ => output.push(c),
+                },
             }
         }
         Display::fmt(&output, f)
@@ -65,7 +80,11 @@ impl Display for LispString {
 
 impl PartialEq for LispString {
     fn eq(&self, other: &Self) -> bool {
+        // the same text only differs if its stand-in chars mean raw bytes in
+        // just one of the strings
         self.inner() == other.inner()
+            && (self.has_raw_bytes() == other.has_raw_bytes()
+                || !self.chars().any(|c| char_to_raw_byte(c).is_some()))
     }
 }
 
@@ -86,12 +105,19 @@ impl Deref for LispString {
 }
 
 impl LispString {
-    pub(in crate::core) unsafe fn new(string: *mut str, constant: bool) -> Self {
-        Self(GcHeap::new(LispStringInner(Cell::new(string)), constant))
+    pub(in crate::core) unsafe fn new(string: *mut str, raw_bytes: bool, constant: bool) -> Self {
+        let inner = LispStringInner { text: Cell::new(string), raw_bytes: Cell::new(raw_bytes) };
+        Self(GcHeap::new(inner, constant))
     }
 
     pub(crate) fn inner(&self) -> &str {
-        unsafe { &*self.0.0.get() }
+        unsafe { &*self.0.text.get() }
+    }
+
+    /// Whether the string holds raw bytes, which means that its chars from
+    /// U+10FF80 to U+10FFFF are eight-bit characters.
+    pub(crate) fn has_raw_bytes(&self) -> bool {
+        self.0.raw_bytes.get()
     }
 }
 
@@ -101,16 +127,52 @@ impl LispString {
     }
 
     pub(crate) fn clear(&self) {
-        let inner_mut_str = unsafe { &mut *self.0.0.get() };
+        let inner_mut_str = unsafe { &mut *self.0.text.get() };
         for byte in unsafe { inner_mut_str.as_bytes_mut().iter_mut() } {
             *byte = b'\0';
         }
     }
+
+    /// Replace every `from` byte with `to` in place. Both have to be ASCII,
+    /// which never occurs inside a multibyte char, so the string stays valid
+    /// UTF-8.
+    pub(crate) fn replace_ascii(&self, from: u8, to: u8) {
+        assert!(from.is_ascii() && to.is_ascii(), "only ASCII can be replaced in place");
+        let inner_mut_str = unsafe { &mut *self.0.text.get() };
+        for byte in unsafe { inner_mut_str.as_bytes_mut().iter_mut() } {
+            if *byte == from {
+                *byte = to;
+            }
+        }
+    }
+
+    /// Replace the text of the string with `text`, which can have a different
+    /// length in bytes. The new text is allocated in `bk`.
+    pub(crate) fn set_text<const C: bool>(
+        &self,
+        text: &str,
+        raw_bytes: bool,
+        bk: &Block<C>,
+    ) -> Result<()> {
+        // a constant string is never copied by the collector, so its text
+        // can't live in a block that gets collected
+        let constant = matches!(self.0.allocation_state(), AllocState::Global);
+        ensure!(!constant, "Attempt to mutate constant String");
+        let mut new = GcString::from_str_in(text, &bk.objects);
+        self.0.text.set(new.as_mut_str());
+        std::mem::forget(new);
+        self.0.raw_bytes.set(raw_bytes);
+        Ok(())
+    }
 }
 
 impl<'new> CloneIn<'new, &'new Self> for LispString {
     fn clone_in<const C: bool>(&self, bk: &'new Block<C>) -> super::Gc<&'new Self> {
-        GcString::from_str_in(self.inner(), &bk.objects).into_obj(bk)
+        if self.has_raw_bytes() {
+            RawBytesText(self.inner().to_owned()).into_obj(bk)
+        } else {
+            GcString::from_str_in(self.inner(), &bk.objects).into_obj(bk)
+        }
     }
 }
 
@@ -186,6 +248,23 @@ impl ByteString {
     pub(crate) fn inner(&self) -> &[u8] {
         unsafe { &**self.0 }
     }
+
+    pub(crate) fn clear(&self) {
+        let inner_mut = unsafe { &mut **self.0 };
+        for byte in inner_mut.iter_mut() {
+            *byte = b'\0';
+        }
+    }
+
+    /// Replace every `from` byte with `to` in place.
+    pub(crate) fn replace_byte(&self, from: u8, to: u8) {
+        let inner_mut = unsafe { &mut **self.0 };
+        for byte in inner_mut.iter_mut() {
+            if *byte == from {
+                *byte = to;
+            }
+        }
+    }
 }
 
 impl<'new> CloneIn<'new, &'new Self> for ByteString {
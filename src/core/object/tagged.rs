@@ -7,8 +7,8 @@ use super::{
     ByteFnPrototype, ByteString, CharTableInner, GcString, LispBigInt, LispBuffer,
 };
 use super::{
-    ByteFn, CharTable, HashTable, LispFloat, LispHashTable, LispString, LispVec, Record,
-    RecordBuilder, SubrFn, Symbol, SymbolCell,
+    ByteFn, CharTable, HashTable, LispFloat, LispHashTable, LispString, LispVec, RawBytesText,
+    Record, RecordBuilder, SubrFn, Symbol, SymbolCell,
 };
 use crate::{
     arith::{MAX_FIXNUM, MIN_FIXNUM},
@@ -408,7 +408,21 @@ impl IntoObject for String {
         unsafe {
             let mut this = self;
             let ptr = this.as_mut_str();
-            let ptr = block.objects.alloc(LispString::new(ptr, C));
+            let ptr = block.objects.alloc(LispString::new(ptr, false, C));
+            block.drop_stack.borrow_mut().push(DropStackElem::String(this));
+            Self::Out::tag_ptr(ptr)
+        }
+    }
+}
+
+impl IntoObject for RawBytesText {
+    type Out<'ob> = <String as IntoObject>::Out<'ob>;
+
+    fn into_obj<const C: bool>(self, block: &Block<C>) -> Gc<Self::Out<'_>> {
+        unsafe {
+            let mut this = self.0;
+            let ptr = this.as_mut_str();
+            let ptr = block.objects.alloc(LispString::new(ptr, true, C));
             block.drop_stack.borrow_mut().push(DropStackElem::String(this));
             Self::Out::tag_ptr(ptr)
         }
@@ -421,7 +435,7 @@ impl IntoObject for GcString<'_> {
     fn into_obj<const C: bool>(self, block: &Block<C>) -> Gc<Self::Out<'_>> {
         unsafe {
             let mut this = self;
-            let ptr = block.objects.alloc(LispString::new(this.as_mut_str(), C));
+            let ptr = block.objects.alloc(LispString::new(this.as_mut_str(), false, C));
             std::mem::forget(this);
             Self::Out::tag_ptr(ptr)
         }
@@ -786,17 +800,6 @@ impl TaggedPtr for i64 {
     }
 }
 
-pub(crate) fn int_to_char(int: i64) -> Result<char, TypeError> {
-    let err = TypeError::new(Type::Char, TagType::tag(int));
-    match u32::try_from(int) {
-        Ok(x) => match char::from_u32(x) {
-            Some(c) => Ok(c),
-            None => Err(err),
-        },
-        Err(_) => Err(err),
-    }
-}
-
 impl TaggedPtr for &LispFloat {
     type Ptr = LispFloat;
     const TAG: Tag = Tag::Float;
@@ -1222,12 +1225,16 @@ impl From<Object<'_>> for () {
 
 pub(crate) type OptionalFlag = Option<()>;
 
+/// An optional argument that is `None` only when it was not given, so an
+/// explicit nil can be told apart from a missing argument.
+pub(crate) type Supplied<'ob> = Option<Object<'ob>>;
+
 impl<'ob> TryFrom<Object<'ob>> for Number<'ob> {
     type Error = TypeError;
 
     fn try_from(value: Object<'ob>) -> Result<Self, Self::Error> {
         match value.get_tag() {
-            Tag::Int | Tag::Float => unsafe { Ok(cast_gc(value)) },
+            Tag::Int | Tag::Float | Tag::BigInt => unsafe { Ok(cast_gc(value)) },
             _ => Err(TypeError::new(Type::Number, value)),
         }
     }
@@ -1637,7 +1644,17 @@ impl<T> fmt::Debug for Gc<T> {
 
 impl<T> PartialEq for Gc<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.ptr == other.ptr || self.as_obj().untag() == other.as_obj().untag()
+        if self.ptr == other.ptr {
+            return true;
+        }
+        match (self.as_obj().untag(), other.as_obj().untag()) {
+            // Pure ASCII text is the same whether it is unibyte or multibyte
+            (ObjectType::String(s), ObjectType::ByteString(b))
+            | (ObjectType::ByteString(b), ObjectType::String(s)) => {
+                b.is_ascii() && s.as_bytes() == b.inner()
+            }
+            (x, y) => x == y,
+        }
     }
 }
 
@@ -1,4 +1,10 @@
 //! Utilities for variables and values.
+use crate::alloc::list;
+use crate::arith::{
+    IntOrBig, MAX_FIXNUM, MIN_FIXNUM, NumberValue, exact_number, parse_digit, parse_int_in_radix,
+    require_integer,
+};
+use crate::character::{code_to_char, require_character, string_codes};
 use crate::core::{
     cons::Cons,
     env::{Env, INTERNED_SYMBOLS, sym},
@@ -8,7 +14,10 @@ use crate::core::{
         IntoObject, List, ListType, NIL, Number, Object, ObjectType, SubrFn, Symbol, WithLifetime,
     },
 };
-use anyhow::{Result, anyhow};
+use crate::floatfns::integer_width;
+use anyhow::{Result, anyhow, bail, ensure};
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
 use rune_core::{hashmap::HashSet, macros::list};
 use rune_macros::defun;
 use std::sync::LazyLock;
@@ -180,12 +189,15 @@ pub(crate) fn subrp(object: Object) -> bool {
 
 #[defun]
 pub(crate) fn stringp(object: Object) -> bool {
-    matches!(object.untag(), ObjectType::String(_))
+    matches!(object.untag(), ObjectType::String(_) | ObjectType::ByteString(_))
 }
 
 #[defun]
 pub(crate) fn numberp(object: Object) -> bool {
-    matches!(object.untag(), ObjectType::Int(_) | ObjectType::Float(_))
+    matches!(
+        object.untag(),
+        ObjectType::Int(_) | ObjectType::Float(_) | ObjectType::BigInt(_)
+    )
 }
 
 #[defun]
@@ -219,7 +231,21 @@ pub(crate) fn keywordp(object: Object) -> bool {
 
 #[defun]
 pub(crate) fn integerp(object: Object) -> bool {
-    matches!(object.untag(), ObjectType::Int(_))
+    matches!(object.untag(), ObjectType::Int(_) | ObjectType::BigInt(_))
+}
+
+#[defun]
+pub(crate) fn natnump(object: Object) -> bool {
+    match object.untag() {
+        ObjectType::Int(x) => x >= 0,
+        ObjectType::BigInt(x) => x.sign() != num_bigint::Sign::Minus,
+        _ => false,
+    }
+}
+
+#[defun]
+pub(crate) fn integer_or_marker_p(object: Object) -> bool {
+    integerp(object) || markerp(object)
 }
 
 #[defun]
@@ -227,6 +253,52 @@ pub(crate) fn floatp(object: Object) -> bool {
     matches!(object.untag(), ObjectType::Float(_))
 }
 
+/// Whether `object` is a number of the numeric type `ty`. A type can be
+/// bounded like `(integer 0 10)`, where the bounds are inclusive and `*`
+/// leaves that side unbounded. Any other type, or a malformed bound, doesn't
+/// match.
+#[defun]
+pub(crate) fn cl_typep_number(object: Object, ty: Object) -> bool {
+    let (ty, bounds) = match ty.untag() {
+        ObjectType::Symbol(ty) => (ty, Vec::new()),
+        ObjectType::Cons(cons) => {
+            let bounds: Option<Vec<_>> =
+                cons.cdr().as_list().ok().and_then(|list| list.collect::<Result<_, _>>().ok());
+            let (Ok(ty), Some(bounds)) = (Symbol::try_from(cons.car()), bounds) else {
+                return false;
+            };
+            (ty, bounds)
+        }
+        _ => return false,
+    };
+    let is_type = match ty {
+        sym::NUMBER => numberp(object),
+        sym::INTEGER => integerp(object),
+        sym::FIXNUM => matches!(object.untag(), ObjectType::Int(_)),
+        sym::BIGNUM => matches!(object.untag(), ObjectType::BigInt(_)),
+        sym::NATNUM => natnump(object),
+        sym::FLOAT => floatp(object),
+        _ => false,
+    };
+    if !is_type || bounds.is_empty() {
+        return is_type;
+    }
+    let (Ok(value), true) = (Number::try_from(object), bounds.len() <= 2) else {
+        return false;
+    };
+    let value = value.val();
+    let in_bound = |bound: Option<&Object>, cmp: fn(&NumberValue, &NumberValue) -> bool| {
+        match bound {
+            // `*` leaves that side unbounded
+            Some(bound) if *bound != sym::MUL => {
+                Number::try_from(*bound).is_ok_and(|bound| cmp(&value, &bound.val()))
+            }
+            _ => true,
+        }
+    };
+    in_bound(bounds.first(), NumberValue::ge) && in_bound(bounds.get(1), NumberValue::le)
+}
+
 #[defun]
 pub(crate) fn atom(object: Object) -> bool {
     !consp(object)
@@ -253,18 +325,124 @@ pub(crate) fn multibyte_string_p(object: Object) -> bool {
     matches!(object.untag(), ObjectType::String(_))
 }
 
+/// Return the longest prefix of `string` that is a number in `base`, and
+/// whether it is a float. Floats are only read in base 10, and only with C
+/// style decimal syntax, so something like `0x1.8p3` stops at the `x`. Like
+/// Emacs there are no digit separators, so `1_000` stops at the `_`.
+fn number_prefix(string: &str, base: u32) -> (&str, bool) {
+    let bytes = string.as_bytes();
+    let skip_digits = |start: usize, radix| {
+        let digits = bytes[start..].iter().take_while(|&&b| parse_digit(b.into(), radix).is_some());
+        start + digits.count()
+    };
+    let int_start = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    let int_end = skip_digits(int_start, base);
+    let has_int = int_end > int_start;
+    if base != 10 {
+        return (if has_int { &string[..int_end] } else { "" }, false);
+    }
+    let (frac_start, frac_end) = match bytes.get(int_end) {
+        Some(b'.') => (int_end + 1, skip_digits(int_end + 1, 10)),
+        _ => (int_end, int_end),
+    };
+    let has_frac = frac_end > frac_start;
+    if !has_int && !has_frac {
+        return ("", false);
+    }
+    // an exponent only counts if it has digits
+    if matches!(bytes.get(frac_end), Some(b'e' | b'E')) {
+        let exp_start = frac_end + 1;
+        let digits_start =
+            exp_start + usize::from(matches!(bytes.get(exp_start), Some(b'+' | b'-')));
+        let exp_end = skip_digits(digits_start, 10);
+        if exp_end > digits_start {
+            return (&string[..exp_end], true);
+        }
+    }
+    if has_frac { (&string[..frac_end], true) } else { (&string[..int_end], false) }
+}
+
+#[defun]
+fn number_to_string(number: Object, cx: &Context) -> Result<String> {
+    Ok(exact_number(number, cx)?.to_emacs_string())
+}
+
 #[defun]
-fn string_to_number<'ob>(string: &str, base: Option<i64>, cx: &'ob Context) -> Number<'ob> {
-    // TODO: Handle trailing characters, which should be ignored
+fn string_to_number(string: &str, base: Option<i64>, cx: &Context) -> Result<NumberValue> {
     let base = base.unwrap_or(10);
-    let string = string.trim();
-    match i64::from_str_radix(string, base as u32) {
-        Ok(x) => x.into(),
-        Err(_) => match string.parse::<f64>() {
-            Ok(x) => cx.add_as(x),
-            Err(_) => 0.into(),
-        },
+    if !(2..=16).contains(&base) {
+        return Err(LispError::args_out_of_range(&[base.into()], cx).into());
+    }
+    // Leading whitespace and anything after the number is ignored
+    let string = string.trim_start();
+    match number_prefix(string, base as u32) {
+        // Values outside the range of a float parse as infinity, which matches
+        // Emacs.
+        (float, true) => Ok(NumberValue::Float(float.parse()?)),
+        (int, false) => Ok(parse_int_in_radix(int, base as u32).unwrap_or(NumberValue::Int(0))),
+    }
+}
+
+defsym!(KW_START);
+defsym!(KW_END);
+defsym!(KW_RADIX);
+defsym!(KW_JUNK_ALLOWED);
+defsym!(RADIX);
+
+#[defun(name = "cl-parse-integer")]
+fn cl_parse_integer(
+    string: &str,
+    keyword_args: &[Object],
+    cx: &Context,
+) -> Result<Option<NumberValue>> {
+    let keyword = |key: Symbol| {
+        let pos = keyword_args.iter().step_by(2).position(|&x| x == key)?;
+        keyword_args.get(pos * 2 + 1).copied()
+    };
+    let arg = |key| keyword(key).map_or(Ok(None), Option::<usize>::try_from);
+    let len = string.chars().count();
+    let start = arg(sym::KW_START)?.unwrap_or(0);
+    let end = arg(sym::KW_END)?.unwrap_or(len);
+    ensure!(start <= end && end <= len, "Bad interval: [{start}, {end})");
+    let radix = check_radix(arg(sym::KW_RADIX)?.map_or(10, |x| x as i64), cx)?;
+    let junk_allowed = keyword(sym::KW_JUNK_ALLOWED).is_some_and(|x| !x.is_nil());
+
+    let substring: String = string.chars().skip(start).take(end - start).collect();
+    let text = substring.trim_start_matches(|c: char| c.is_ascii_whitespace());
+    let sign_len = usize::from(text.starts_with(['+', '-']));
+    let digits_end = match text[sign_len..].find(|c| parse_digit(c, radix).is_none()) {
+        Some(idx) => sign_len + idx,
+        None => text.len(),
+    };
+    let (number, junk) = text.split_at(digits_end);
+    if junk_allowed {
+        return Ok(parse_int_in_radix(number, radix).ok());
+    }
+    let not_integer = || anyhow!("Not an integer string: `{string}'");
+    if !junk.trim_start_matches(|c: char| c.is_ascii_whitespace()).is_empty() {
+        return Err(not_integer());
+    }
+    parse_int_in_radix(number, radix).map(Some).map_err(|_| not_integer())
+}
+
+/// `radix` if it is a valid radix for `cl-parse-integer` and
+/// `cl-digit-char-p`, which signal `(args-out-of-range radix RADIX (2 36))`
+/// otherwise.
+fn check_radix(radix: i64, cx: &Context) -> Result<u32> {
+    if (2..=36).contains(&radix) {
+        return Ok(radix as u32);
     }
+    let range = list![2, 36; cx];
+    Err(LispError::args_out_of_range(&[sym::RADIX.into(), radix.into(), range], cx).into())
+}
+
+/// The value of `chr` as a digit in `radix`, with the same digits as
+/// `cl-parse-integer`.
+#[defun]
+fn cl_digit_char_p(chr: Object, radix: Option<i64>, cx: &Context) -> Result<Option<u32>> {
+    let radix = check_radix(radix.unwrap_or(10), cx)?;
+    let code = require_character(chr, cx)?;
+    Ok(code_to_char(code).and_then(|chr| parse_digit(chr, radix)))
 }
 
 #[defun]
@@ -297,11 +475,42 @@ fn subr_arity<'ob>(subr: &SubrFn, cx: &'ob Context) -> Object<'ob> {
     Cons::new(min, max, cx).into()
 }
 
+/// Shift `value` left by `count` bits, or right when `count` is negative.
+/// Right shifts round toward negative infinity like a two's complement shift.
 #[defun]
-fn ash(value: i64, count: i64) -> i64 {
-    let shift = if count >= 0 { std::ops::Shl::shl } else { std::ops::Shr::shr };
-    let result = shift(value.abs(), count.abs());
-    if value >= 0 { result } else { -result }
+fn ash(value: Object, count: Object, env: &Rt<Env>, cx: &Context) -> Result<NumberValue> {
+    let value = require_integer(value, cx)?;
+    let count = require_integer(count, cx)?;
+    if let (IntOrBig::Int(value), IntOrBig::Int(count)) = (value, count) {
+        if count <= 0 {
+            return Ok(NumberValue::Int(value >> count.unsigned_abs().min(63)));
+        }
+        if count < 64 && (value << count) >> count == value {
+            let result = value << count;
+            if (MIN_FIXNUM..=MAX_FIXNUM).contains(&result) {
+                return Ok(NumberValue::Int(result));
+            }
+        }
+    }
+    let value = BigInt::from(value);
+    let count = BigInt::from(count);
+    if value.is_zero() {
+        return Ok(NumberValue::Int(0));
+    }
+    if count.is_negative() {
+        // shifting out every bit leaves only the sign
+        let shifted = match count.magnitude().to_u64() {
+            Some(count) if count < value.bits() => value >> count,
+            _ => BigInt::from(if value.is_negative() { -1 } else { 0 }),
+        };
+        return Ok(NumberValue::Big(shifted).coerce_integer());
+    }
+    // check the size first so a huge count can't exhaust memory
+    let max_bits = integer_width(env, cx);
+    let Some(count) = count.to_u64().filter(|&count| value.bits() + count <= max_bits) else {
+        bail!("Integer too large")
+    };
+    Ok(NumberValue::Big(value << count).coerce_integer())
 }
 
 #[defun]
@@ -339,6 +548,21 @@ pub(crate) fn aset<'ob>(
     }
 }
 
+/// Return the element at character index `idx` of `string`. This is the
+/// string case of both `aref` and `elt`.
+pub(crate) fn string_char_at(string: Object, idx: usize) -> Result<i64> {
+    let Some(codes) = string_codes(string) else {
+        return Err(TypeError::new(Type::String, string).into());
+    };
+    match codes.clone().nth(idx) {
+        Some(code) => Ok(code),
+        None => {
+            let len = codes.count();
+            Err(anyhow!("index {idx} is out of bounds. Length was {len}"))
+        }
+    }
+}
+
 #[defun]
 pub(crate) fn aref<'ob>(array: Object<'ob>, idx: usize, cx: &'ob Context) -> Result<Object<'ob>> {
     match array.untag() {
@@ -356,20 +580,7 @@ pub(crate) fn aref<'ob>(array: Object<'ob>, idx: usize, cx: &'ob Context) -> Res
                 Err(anyhow!("index {idx} is out of bounds. Length was {len}"))
             }
         },
-        ObjectType::String(string) => match string.chars().nth(idx) {
-            Some(x) => Ok((i64::from(x as u32)).into()),
-            None => {
-                let len = string.len();
-                Err(anyhow!("index {idx} is out of bounds. Length was {len}"))
-            }
-        },
-        ObjectType::ByteString(string) => match string.get(idx) {
-            Some(x) => Ok((i64::from(*x)).into()),
-            None => {
-                let len = string.len();
-                Err(anyhow!("index {idx} is out of bounds. Length was {len}"))
-            }
-        },
+        ObjectType::String(_) | ObjectType::ByteString(_) => Ok(string_char_at(array, idx)?.into()),
         ObjectType::ByteFn(fun) => match fun.index(idx, cx) {
             Some(x) => Ok(x),
             None => Err(anyhow!("index {idx} is out of bounds")),
@@ -495,6 +706,13 @@ impl std::fmt::Display for LispError {
 }
 
 defsym!(WRONG_NUMBER_OF_ARGUMENTS);
+defsym!(RANGE_ERROR);
+defsym!(WRONG_TYPE_ARGUMENT);
+defsym!(ARITH_ERROR);
+defsym!(TYPE_MISMATCH);
+defsym!(WRONG_LENGTH_ARGUMENT);
+defsym!(ARGS_OUT_OF_RANGE);
+defsym!(CODING_SYSTEM_ERROR);
 impl LispError {
     pub(crate) fn new(message: &Cons) -> Self {
         Self { message: unsafe { message.with_lifetime() } }
@@ -514,6 +732,52 @@ impl LispError {
         let list = list![sym::WRONG_NUMBER_OF_ARGUMENTS, func, expected, actual; cx];
         Self::new(list.try_into().unwrap())
     }
+
+    pub(crate) fn wrong_type(predicate: Symbol, value: Object, cx: &Context) -> Self {
+        let list = list![sym::WRONG_TYPE_ARGUMENT, predicate, value; cx];
+        Self::new(list.try_into().unwrap())
+    }
+
+    /// The `arith-error` signal for an integer division by zero.
+    pub(crate) fn arith_error(cx: &Context) -> Self {
+        let list = list![sym::ARITH_ERROR; cx];
+        Self::new(list.try_into().unwrap())
+    }
+
+    /// The `wrong-length-argument` signal for an argument of the wrong
+    /// length.
+    pub(crate) fn wrong_length(length: usize, cx: &Context) -> Self {
+        let list = list![sym::WRONG_LENGTH_ARGUMENT, length; cx];
+        Self::new(list.try_into().unwrap())
+    }
+
+    /// The `type-mismatch` signal for two values that cannot be compared.
+    pub(crate) fn type_mismatch(a: Object, b: Object, cx: &Context) -> Self {
+        let list = list![sym::TYPE_MISMATCH, a, b; cx];
+        Self::new(list.try_into().unwrap())
+    }
+
+    /// The `args-out-of-range` signal for `values` that are outside the range
+    /// their function accepts.
+    pub(crate) fn args_out_of_range(values: &[Object], cx: &Context) -> Self {
+        Self::new(Cons::new(sym::ARGS_OUT_OF_RANGE, list(values, cx), cx))
+    }
+
+    /// The `coding-system-error` signal for a coding system that isn't known.
+    pub(crate) fn coding_system_error(coding_system: Object, cx: &Context) -> Self {
+        let list = list![sym::CODING_SYSTEM_ERROR, coding_system; cx];
+        Self::new(list.try_into().unwrap())
+    }
+
+    pub(crate) fn range_error<'ob, T>(
+        func: &str,
+        value: impl IntoObject<Out<'ob> = T>,
+        cx: &'ob Context,
+    ) -> Self {
+        let value = value.into_obj(cx);
+        let list = list![sym::RANGE_ERROR, func, value; cx];
+        Self::new(list.try_into().unwrap())
+    }
 }
 
 unsafe impl Send for LispError {}
@@ -522,15 +786,197 @@ unsafe impl Sync for LispError {}
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::interpreter::assert_lisp;
+    use crate::interpreter::{assert_lisp, assert_lisp_signal};
+
+    #[test]
+    fn test_string_to_number() {
+        assert_lisp("(string-to-number \"42\")", "42");
+        assert_lisp("(string-to-number \" -17 \")", "-17");
+        assert_lisp("(string-to-number \"ff\" 16)", "255");
+        assert_lisp("(string-to-number \"1.5\")", "1.5");
+        assert_lisp("(string-to-number \"foo\")", "0");
+        assert_lisp("(string-to-number \"inf\")", "0");
+        assert_lisp(
+            "(string-to-number \"99999999999999999999999999\")",
+            "99999999999999999999999999",
+        );
+        assert_lisp("(integerp (string-to-number \"99999999999999999999999999\"))", "t");
+        assert_lisp("(= (string-to-number \"1e400\") (/ 1.0 0.0))", "t");
+        assert_lisp("(= (string-to-number \"-1e400\") (/ -1.0 0.0))", "t");
+        assert_lisp_signal("(string-to-number \"1\" 17)", "(args-out-of-range 17)");
+    }
+
+    #[test]
+    fn test_number_to_string() {
+        assert_lisp("(number-to-string 42)", "\"42\"");
+        assert_lisp("(number-to-string -7)", "\"-7\"");
+        assert_lisp("(number-to-string 1.5)", "\"1.5\"");
+        assert_lisp("(number-to-string (expt 2 70))", "\"1180591620717411303424\"");
+        // negative zero keeps its sign
+        assert_lisp("(number-to-string 0.0)", "\"0.0\"");
+        assert_lisp("(number-to-string -0.0)", "\"-0.0\"");
+        assert_lisp("(number-to-string (- 0.0))", "\"-0.0\"");
+        assert_lisp("(format \"%s %S\" -0.0 -0.0)", "\"-0.0 -0.0\"");
+        assert_lisp("(format \"%g %f\" -0.0 -0.0)", "\"-0 -0.000000\"");
+    }
+
+    #[test]
+    fn test_float_decimal_point() {
+        // floats are written by Rust rather than libc, so the decimal point is
+        // `.` without depending on the locale of the process
+        let write = |float| {
+            let mut string = String::new();
+            crate::core::object::write_float(&mut string, float).unwrap();
+            string
+        };
+        assert_eq!(write(1.5), "1.5");
+        assert_eq!(write(-1234.25), "-1234.25");
+        assert_eq!(write(2.0), "2.0");
+        assert_lisp("(number-to-string 1.5)", "\"1.5\"");
+        assert_lisp("(number-to-string -1234.25)", "\"-1234.25\"");
+        assert_lisp("(format \"%f\" 1.5)", "\"1.500000\"");
+        assert_lisp("(format \"%s %S\" 0.5 2.0)", "\"0.5 2.0\"");
+        assert_lisp("(string-to-number \"1.5\")", "1.5");
+    }
+
+    #[test]
+    fn test_string_to_number_prefix() {
+        // hex floats are not C syntax in Emacs, so this stops at the x
+        assert_lisp("(string-to-number \"0x1.8p3\")", "0");
+        assert_lisp("(string-to-number \"0\")", "0");
+        assert_lisp("(string-to-number \"0x10\" 16)", "0");
+        assert_lisp("(string-to-number \"12abc\")", "12");
+        assert_lisp("(string-to-number \"ffz\" 16)", "4095");
+        assert_lisp("(string-to-number \"1.5e3x\")", "1500.0");
+        assert_lisp("(string-to-number \"1.5p3\")", "1.5");
+        assert_lisp("(string-to-number \"1e\")", "1");
+        assert_lisp("(string-to-number \"1e+\")", "1");
+        assert_lisp("(string-to-number \"1.\")", "1");
+        assert_lisp("(string-to-number \"1.e2\")", "100.0");
+        assert_lisp("(string-to-number \"-.5\")", "-0.5");
+        assert_lisp("(string-to-number \".e5\")", "0");
+        assert_lisp("(string-to-number \"-\")", "0");
+        assert_lisp("(string-to-number \"1.5\" 16)", "1");
+    }
+
+    #[test]
+    fn test_no_digit_separators() {
+        // Emacs has no digit separators, so an underscore ends the number
+        assert_lisp("(string-to-number \"1_000\")", "1");
+        assert_lisp("(string-to-number \"ff_ff\" 16)", "255");
+        assert_lisp("(string-to-number \"1.5_0\")", "1.5");
+        assert_lisp("(string-to-number \"1e1_0\")", "10.0");
+        assert_lisp("(string-to-number \"_1\")", "0");
+        assert_lisp("(condition-case nil (cl-parse-integer \"1_000\") (error 'junk))", "junk");
+        assert_lisp("(cl-parse-integer \"1_000\" :junk-allowed t)", "1");
+        // and the reader takes it as a symbol
+        assert_lisp("(symbolp '1_000)", "t");
+    }
+
+    #[test]
+    fn test_digit_case() {
+        // letter digits are accepted in either case everywhere
+        assert_lisp("(= (string-to-number \"FF\" 16) (string-to-number \"ff\" 16))", "t");
+        assert_lisp("(string-to-number \"Ff\" 16)", "255");
+        assert_lisp(
+            "(= (cl-parse-integer \"FF\" :radix 16) (cl-parse-integer \"ff\" :radix 16))",
+            "t",
+        );
+        assert_lisp("(cl-parse-integer \"Z\" :radix 36)", "35");
+        assert_lisp("(cl-parse-integer \"z\" :radix 36)", "35");
+        assert_lisp("(cl-parse-integer \"-Zz\" :radix 36)", "-1295");
+        assert_lisp(
+            "(cl-parse-integer \"ABCDEFABCDEFABCDEF\" :radix 16)",
+            "3169232504802520059375",
+        );
+        assert_lisp("(cl-digit-char-p ?Z 36)", "35");
+        assert_lisp("(cl-digit-char-p ?z 36)", "35");
+        assert_lisp("(cl-digit-char-p ?F 16)", "15");
+        assert_lisp("(cl-digit-char-p ?7)", "7");
+        assert_lisp("(cl-digit-char-p ?a)", "nil");
+        assert_lisp("(cl-digit-char-p ?g 16)", "nil");
+        assert_lisp("(cl-digit-char-p ?λ 36)", "nil");
+        assert_lisp_signal("(cl-digit-char-p ?a 37)", "(args-out-of-range radix 37 (2 36))");
+    }
+
+    #[test]
+    fn test_cl_parse_integer() {
+        assert_lisp("(cl-parse-integer \"42\")", "42");
+        assert_lisp("(cl-parse-integer \"  -42  \")", "-42");
+        assert_lisp("(cl-parse-integer \"101\" :radix 2)", "5");
+        assert_lisp("(cl-parse-integer \"777\" :radix 8)", "511");
+        assert_lisp("(cl-parse-integer \"FF\" :radix 16)", "255");
+        assert_lisp("(cl-parse-integer \"zz\" :radix 36)", "1295");
+        assert_lisp(
+            "(cl-parse-integer \"ffffffffffffffffffff\" :radix 16)",
+            "1208925819614629174706175",
+        );
+        assert_lisp("(cl-parse-integer \"x123y\" :start 1 :end 4)", "123");
+        assert_lisp("(cl-parse-integer \"12abc\" :junk-allowed t)", "12");
+        assert_lisp("(cl-parse-integer \"abc\" :junk-allowed t)", "nil");
+        assert_lisp("(condition-case nil (cl-parse-integer \"12abc\") (error 'junk))", "junk");
+        assert_lisp("(condition-case nil (cl-parse-integer \"\") (error 'empty))", "empty");
+        assert_lisp_signal(
+            "(cl-parse-integer \"1\" :radix 37)",
+            "(args-out-of-range radix 37 (2 36))",
+        );
+    }
+
+    #[test]
+    fn test_cl_typep_number() {
+        assert_lisp("(cl-typep-number 5 'integer)", "t");
+        assert_lisp("(cl-typep-number 5 'float)", "nil");
+        assert_lisp("(cl-typep-number 5.0 'number)", "t");
+        assert_lisp("(cl-typep-number 5 'fixnum)", "t");
+        assert_lisp("(cl-typep-number 5 'bignum)", "nil");
+        assert_lisp("(cl-typep-number (expt 2 70) 'bignum)", "t");
+        assert_lisp("(cl-typep-number -1 'natnum)", "nil");
+        assert_lisp("(cl-typep-number 5 '(integer 0 10))", "t");
+        assert_lisp("(cl-typep-number 10 '(integer 0 10))", "t");
+        assert_lisp("(cl-typep-number 11 '(integer 0 10))", "nil");
+        assert_lisp("(cl-typep-number -1 '(integer 0 *))", "nil");
+        assert_lisp("(cl-typep-number (expt 2 70) '(integer 0 *))", "t");
+        assert_lisp("(cl-typep-number 0.5 '(float 0.0 1.0))", "t");
+        assert_lisp("(cl-typep-number 5.0 '(integer 0 10))", "nil");
+        // only numeric types match
+        assert_lisp("(cl-typep-number \"a\" 'string)", "nil");
+        assert_lisp("(cl-typep-number 5 'foo)", "nil");
+        assert_lisp("(cl-typep-number 5 '(integer a 10))", "nil");
+        assert_lisp("(cl-typep-number 5 '(integer 0 10 20))", "nil");
+    }
 
     #[test]
     fn test_ash() {
-        assert_eq!(ash(4, 1), 8);
-        assert_eq!(ash(4, -1), 2);
-        assert_eq!(ash(-8, -1), -4);
-        assert_eq!(ash(256, -8), 1);
-        assert_eq!(ash(-8, 1), -16);
+        assert_lisp("(ash 4 1)", "8");
+        assert_lisp("(ash 4 -1)", "2");
+        assert_lisp("(ash -8 -1)", "-4");
+        assert_lisp("(ash 256 -8)", "1");
+        assert_lisp("(ash -8 1)", "-16");
+        // right shifts round down
+        assert_lisp("(ash -1 -1)", "-1");
+        assert_lisp("(ash -7 -1)", "-4");
+        assert_lisp("(ash 5 -100)", "0");
+        assert_lisp("(ash -5 -100)", "-1");
+        // results that leave the fixnum range become bignums
+        assert_lisp("(ash 1 70)", "1180591620717411303424");
+        assert_lisp("(ash -3 62)", "-13835058055282163712");
+        assert_lisp("(ash (expt 2 70) -69)", "2");
+        assert_lisp("(cl-typep-number (ash (expt 2 70) -20) 'fixnum)", "t");
+        assert_lisp("(ash (- (expt 2 70)) -200)", "-1");
+    }
+
+    #[test]
+    fn test_ash_count() {
+        assert_lisp_signal("(ash 1 1.0)", "(wrong-type-argument integerp 1.0)");
+        assert_lisp_signal("(ash 1.0 1)", "(wrong-type-argument integerp 1.0)");
+        assert_lisp_signal("(ash 1 'a)", "(wrong-type-argument integerp a)");
+        // bignum counts are fine as long as the result is reasonable
+        assert_lisp("(ash 12345 (- (expt 2 70)))", "0");
+        assert_lisp("(ash -12345 (- (expt 2 70)))", "-1");
+        assert_lisp("(ash 0 (expt 2 70))", "0");
+        assert_lisp_signal("(ash 1 (expt 2 70))", "(error \"Integer too large\")");
+        assert_lisp_signal("(ash 1 100000)", "(error \"Integer too large\")");
+        assert_lisp("(let ((integer-width 200)) (= (ash 1 150) (expt 2 150)))", "t");
     }
 
     #[test]
@@ -548,3 +994,7 @@ defsym!(BUFFER);
 defsym!(SUBR);
 defsym!(CHAR_TABLE);
 defsym!(BIG_INT);
+defsym!(NUMBER);
+defsym!(FIXNUM);
+defsym!(BIGNUM);
+defsym!(NATNUM);
@@ -1,28 +1,48 @@
 //! Buffer editing utilities.
-use crate::core::{
-    env::{ArgSlice, Env},
-    gc::{Context, Rt},
-    object::{Object, ObjectType},
+use crate::{
+    buffer::case_fold_search,
+    character::{
+        EIGHT_BIT_BASE, MultibyteText, char_code, code_to_char, fold_char, is_eight_bit,
+        raw_byte_to_char, require_character, string_text, text_has_raw_bytes,
+    },
+    core::{
+        env::{ArgSlice, Env},
+        error::{Type, TypeError},
+        gc::{Context, Rt},
+        object::{Gc, LispString, Object, ObjectType},
+    },
 };
 use anyhow::{Result, bail, ensure};
+use num_bigint::{BigInt, Sign};
+use num_traits::{FromPrimitive, ToPrimitive, Zero};
 use rune_macros::defun;
 use std::{fmt::Write as _, io::Write};
 
 #[defun]
-fn message(format_string: &str, args: &[Object]) -> Result<String> {
-    let message = format(format_string, args)?;
-    println!("MESSAGE: {message}");
+fn message<'ob>(format_string: &str, args: &[Object], cx: &'ob Context) -> Result<Object<'ob>> {
+    let message = format_text(format_string, args)?;
+    println!("MESSAGE: {}", message.as_str());
     std::io::stdout().flush()?;
-    Ok(message)
+    Ok(cx.add(message))
 }
 
 defvar!(MESSAGE_NAME);
 defvar!(MESSAGE_TYPE, "new message");
 
+const TYPE_MISMATCH: &str = "Format specifier doesn't match argument type";
+const MIXED_FIELDS: &str = "Format string mixes numbered and unnumbered arguments";
+
 #[defun]
-fn format(string: &str, objects: &[Object]) -> Result<String> {
-    let mut result = String::new();
+fn format<'ob>(string: &str, objects: &[Object], cx: &'ob Context) -> Result<Object<'ob>> {
+    Ok(cx.add(format_text(string, objects)?))
+}
+
+/// The text of the string made by `format`. Raw bytes in the arguments stay
+/// raw bytes in the result.
+fn format_text(string: &str, objects: &[Object]) -> Result<MultibyteText> {
+    let mut result = MultibyteText::default();
     let mut arguments = objects.iter();
+    let mut positional = None;
     let mut remaining = string;
 
     let mut escaped = false;
@@ -38,48 +58,345 @@ fn format(string: &str, objects: &[Object]) -> Result<String> {
         }
     };
     while let Some(start) = remaining.find(&mut is_format_char) {
-        result += &remaining[..start];
-        let Some(specifier) = remaining.as_bytes().get(start + 1) else {
-            bail!("Format string ends in middle of format specifier")
-        };
+        result.push_str(&remaining[..start], false)?;
+        let (spec, conversion, rest) = FormatSpec::parse(&remaining[start + 1..])?;
         // "%%" inserts a single "%" in the output
-        if *specifier == b'%' {
-            result.push('%');
+        if conversion == '%' {
+            result.push_str("%", false)?;
         } else {
-            // TODO: currently handles all format types the same. Need to check the modifier characters.
-            let Some(val) = arguments.next() else {
-                bail!("Not enough arguments for format string")
+            // Arguments are either all numbered like `%2$s` or all taken in
+            // order, since mixing them is ambiguous.
+            let val = match (spec.field, positional) {
+                (Some(_), Some(false)) | (None, Some(true)) => bail!(MIXED_FIELDS),
+                (Some(field), _) => {
+                    positional = Some(true);
+                    objects.get(field - 1)
+                }
+                (None, _) => {
+                    positional = Some(false);
+                    arguments.next()
+                }
             };
-            match val.untag() {
-                ObjectType::String(string) => write!(result, "{string}")?,
-                obj => write!(result, "{obj}")?,
+            let Some(val) = val else { bail!("Not enough arguments for format string") };
+            // each directive is formatted on its own, so the raw bytes of a
+            // string argument are only raw bytes in its own output
+            let mut output = String::new();
+            let mut raw_bytes = text_has_raw_bytes(*val);
+            match conversion {
+                'd' | 'o' | 'x' | 'X' => format_integer(&mut output, &spec, conversion, *val)?,
+                's' => match string_text(*val) {
+                    // Unlike the printer, %s does not quote or escape strings
+                    Some(string) => format_string(&mut output, &spec, &string),
+                    None => format_string(&mut output, &spec, &val.to_string()),
+                },
+                // %S uses the printer, so the output can be read back
+                'S' => format_string(&mut output, &spec, &val.to_string()),
+                'c' => {
+                    let code = match val.untag() {
+                        ObjectType::Int(int) => int,
+                        _ => bail!(TYPE_MISMATCH),
+                    };
+                    let chr = match code_to_char(code) {
+                        Some(chr) => chr,
+                        None if is_eight_bit(code) => {
+                            raw_byte_to_char((code - EIGHT_BIT_BASE) as u8)
+                        }
+                        None => bail!(TYPE_MISMATCH),
+                    };
+                    raw_bytes = is_eight_bit(code);
+                    format_string(&mut output, &spec, chr.encode_utf8(&mut [0; 4]));
+                }
+                'f' | 'e' | 'g' => format_float(&mut output, &spec, conversion, *val)?,
+                _ => match string_text(*val) {
+                    Some(string) => output.push_str(&string),
+                    None => write!(output, "{val}")?,
+                },
             }
+            result.push_str(&output, raw_bytes)?;
         }
-        remaining = &remaining[start + 2..];
+        remaining = rest;
     }
-    result += remaining;
-    ensure!(arguments.next().is_none(), "Too many arguments for format string");
+    result.push_str(remaining, false)?;
+    // numbered arguments don't all have to be used
+    let unused = positional != Some(true) && arguments.next().is_some();
+    ensure!(!unused, "Too many arguments for format string");
     Ok(result)
 }
 
+/// The argument number, flags, field width, and precision of a format
+/// directive.
+#[derive(Debug, Default)]
+#[expect(clippy::struct_excessive_bools)]
+struct FormatSpec {
+    /// The 1-based argument given like `%2$s`, or `None` for the next one
+    field: Option<usize>,
+    left_align: bool,
+    zero_pad: bool,
+    plus_sign: bool,
+    space_sign: bool,
+    alternate: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl FormatSpec {
+    /// Parse the directive following a `%`. Returns the spec, the conversion
+    /// character, and the remainder of the format string.
+    fn parse(directive: &str) -> Result<(Self, char, &str)> {
+        let mut spec = Self::default();
+        let mut rest = directive;
+        let (field, tail) = split_number(rest);
+        if let Some(tail) = tail.strip_prefix('$') {
+            ensure!(field.is_some_and(|x| x > 0), "Invalid format field number");
+            spec.field = field;
+            rest = tail;
+        }
+        loop {
+            match rest.as_bytes().first() {
+                Some(b'-') => spec.left_align = true,
+                Some(b'0') => spec.zero_pad = true,
+                Some(b'+') => spec.plus_sign = true,
+                Some(b' ') => spec.space_sign = true,
+                Some(b'#') => spec.alternate = true,
+                _ => break,
+            }
+            rest = &rest[1..];
+        }
+        let (width, tail) = split_number(rest);
+        spec.width = width.unwrap_or(0);
+        rest = tail;
+        if let Some(tail) = rest.strip_prefix('.') {
+            let (precision, tail) = split_number(tail);
+            spec.precision = Some(precision.unwrap_or(0));
+            rest = tail;
+        }
+        let mut chars = rest.chars();
+        let Some(conversion) = chars.next() else {
+            bail!("Format string ends in middle of format specifier")
+        };
+        Ok((spec, conversion, chars.as_str()))
+    }
+
+    /// Write `body` to `result` padded to the field width. The `prefix` (sign
+    /// and radix marker) is kept in front of any zero padding.
+    fn pad(&self, result: &mut String, prefix: &str, body: &str, zero_pad: bool) {
+        let len = prefix.chars().count() + body.chars().count();
+        let fill = self.width.saturating_sub(len);
+        if self.left_align {
+            result.push_str(prefix);
+            result.push_str(body);
+            result.extend(std::iter::repeat_n(' ', fill));
+        } else if zero_pad {
+            result.push_str(prefix);
+            result.extend(std::iter::repeat_n('0', fill));
+            result.push_str(body);
+        } else {
+            result.extend(std::iter::repeat_n(' ', fill));
+            result.push_str(prefix);
+            result.push_str(body);
+        }
+    }
+}
+
+/// Split the leading decimal digits off of `string`.
+fn split_number(string: &str) -> (Option<usize>, &str) {
+    let end = string.find(|c: char| !c.is_ascii_digit()).unwrap_or(string.len());
+    (string[..end].parse().ok(), &string[end..])
+}
+
+fn format_string(result: &mut String, spec: &FormatSpec, string: &str) {
+    let string = match spec.precision {
+        Some(precision) => match string.char_indices().nth(precision) {
+            Some((idx, _)) => &string[..idx],
+            None => string,
+        },
+        None => string,
+    };
+    spec.pad(result, "", string, false);
+}
+
+/// Format an integer directive. Floats are truncated toward zero, and all
+/// values go through [`BigInt`] so that bignums are printed exactly.
+fn format_integer(
+    result: &mut String,
+    spec: &FormatSpec,
+    conversion: char,
+    val: Object,
+) -> Result<()> {
+    let int = match val.untag() {
+        ObjectType::Int(int) => BigInt::from(int),
+        ObjectType::BigInt(int) => (**int).clone(),
+        ObjectType::Float(float) => match BigInt::from_f64(float.trunc()) {
+            Some(int) => int,
+            None => bail!("Cannot format {float} as an integer"),
+        },
+        _ => bail!(TYPE_MISMATCH),
+    };
+    // to_str_radix converts in subquadratic time, so huge bignums are fine
+    let magnitude = int.magnitude();
+    let mut digits = match conversion {
+        'o' => magnitude.to_str_radix(8),
+        'x' => magnitude.to_str_radix(16),
+        'X' => magnitude.to_str_radix(16).to_ascii_uppercase(),
+        _ => magnitude.to_str_radix(10),
+    };
+    if let Some(precision) = spec.precision {
+        let zeros = precision.saturating_sub(digits.len());
+        digits.insert_str(0, &"0".repeat(zeros));
+    }
+    let mut prefix = String::new();
+    if int.sign() == Sign::Minus {
+        prefix.push('-');
+    } else if spec.plus_sign {
+        prefix.push('+');
+    } else if spec.space_sign {
+        prefix.push(' ');
+    }
+    if spec.alternate {
+        match conversion {
+            'o' if !digits.starts_with('0') => prefix.push('0'),
+            'x' if !int.is_zero() => prefix.push_str("0x"),
+            'X' if !int.is_zero() => prefix.push_str("0X"),
+            _ => {}
+        }
+    }
+    // Like C, the 0 flag is ignored when a precision is given
+    let zero_pad = spec.zero_pad && spec.precision.is_none();
+    spec.pad(result, &prefix, &digits, zero_pad);
+    Ok(())
+}
+
+/// Format a float directive like C's `printf`. The precision defaults to 6
+/// digits, and integers are converted to floats first.
+fn format_float(
+    result: &mut String,
+    spec: &FormatSpec,
+    conversion: char,
+    val: Object,
+) -> Result<()> {
+    let float = match val.untag() {
+        ObjectType::Int(int) => int as f64,
+        ObjectType::BigInt(int) => int.to_f64().unwrap_or(f64::NAN),
+        ObjectType::Float(float) => **float,
+        _ => bail!(TYPE_MISMATCH),
+    };
+    let mut prefix = String::new();
+    if float.is_sign_negative() {
+        prefix.push('-');
+    } else if spec.plus_sign {
+        prefix.push('+');
+    } else if spec.space_sign {
+        prefix.push(' ');
+    }
+    let magnitude = float.abs();
+    if !magnitude.is_finite() {
+        let body = if magnitude.is_nan() { "nan" } else { "inf" };
+        spec.pad(result, &prefix, body, false);
+        return Ok(());
+    }
+    let precision = spec.precision.unwrap_or(6);
+    let mut body = match conversion {
+        'f' => format!("{magnitude:.precision$}"),
+        'e' => exponent_notation(magnitude, precision),
+        _ => {
+            // %g uses the shorter of %e and %f for the number of significant
+            // digits, then drops trailing zeros
+            let digits = precision.max(1);
+            let exponent = decimal_exponent(magnitude, digits - 1);
+            let mut body = match i64::try_from(digits) {
+                Ok(digits) if (-4..digits).contains(&exponent) => {
+                    let decimals = (digits - 1 - exponent) as usize;
+                    format!("{magnitude:.decimals$}")
+                }
+                _ => exponent_notation(magnitude, digits - 1),
+            };
+            if !spec.alternate {
+                strip_trailing_zeros(&mut body);
+            }
+            body
+        }
+    };
+    if spec.alternate && !body.contains('.') {
+        let end = body.find('e').unwrap_or(body.len());
+        body.insert(end, '.');
+    }
+    spec.pad(result, &prefix, &body, spec.zero_pad);
+    Ok(())
+}
+
+/// Write `float` like C's `%e`, where the exponent has a sign and at least two
+/// digits.
+fn exponent_notation(float: f64, precision: usize) -> String {
+    let formatted = format!("{float:.precision$e}");
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let exponent: i64 = exponent.parse().unwrap();
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{mantissa}e{sign}{:02}", exponent.abs())
+}
+
+/// The decimal exponent of `float` once it is rounded to `precision` digits
+/// after the first one.
+fn decimal_exponent(float: f64, precision: usize) -> i64 {
+    let formatted = format!("{float:.precision$e}");
+    formatted.split_once('e').unwrap().1.parse().unwrap()
+}
+
+/// Remove the zeros at the end of the fraction, and the decimal point if that
+/// leaves no fraction.
+fn strip_trailing_zeros(body: &mut String) {
+    let end = body.find('e').unwrap_or(body.len());
+    if !body[..end].contains('.') {
+        return;
+    }
+    let trimmed = body[..end].trim_end_matches('0').trim_end_matches('.').len();
+    body.replace_range(trimmed..end, "");
+}
+
 #[defun]
-fn format_message(string: &str, objects: &[Object]) -> Result<String> {
-    let formatted = format(string, objects)?;
+fn format_message<'ob>(string: &str, objects: &[Object], cx: &'ob Context) -> Result<Object<'ob>> {
+    let formatted: Gc<&LispString> = cx.add_as(format_text(string, objects)?);
     // TODO: implement support for `text-quoting-style`.
-    Ok(formatted
-        .chars()
-        .map(|c| if matches!(c, '`' | '\'') { '"' } else { c })
-        .collect())
+    formatted.untag().replace_ascii(b'`', b'"');
+    formatted.untag().replace_ascii(b'\'', b'"');
+    Ok(formatted.into())
 }
 
 #[defun]
-fn string_to_char(string: &str) -> char {
-    string.chars().next().unwrap_or('\0')
+fn char_equal(c1: Object, c2: Object, env: &Rt<Env>, cx: &Context) -> Result<bool> {
+    let c1 = require_character(c1, cx)?;
+    let c2 = require_character(c2, cx)?;
+    if c1 == c2 {
+        return Ok(true);
+    }
+    if !case_fold_search(env) {
+        return Ok(false);
+    }
+    // codes that are not Unicode have no case
+    let (Some(c1), Some(c2)) = (code_to_char(c1), code_to_char(c2)) else { return Ok(false) };
+    Ok(fold_char(c1).eq(fold_char(c2)))
 }
 
 #[defun]
-fn char_to_string(chr: char) -> String {
-    format!("{chr}")
+fn string_to_char(string: Object) -> Result<i64> {
+    match string.untag() {
+        ObjectType::String(string) => {
+            Ok(string.chars().next().map_or(0, |c| char_code(c, string.has_raw_bytes())))
+        }
+        ObjectType::ByteString(string) => Ok(string.first().map_or(0, |&b| i64::from(b))),
+        _ => Err(TypeError::new(Type::String, string).into()),
+    }
+}
+
+#[defun]
+fn char_to_string<'ob>(chr: Object, cx: &'ob Context) -> Result<Object<'ob>> {
+    let code = require_character(chr, cx)?;
+    // like `string`, an ASCII char makes a unibyte string
+    if let Ok(byte @ 0..0x80) = u8::try_from(code) {
+        return Ok(cx.add(vec![byte]));
+    }
+    let mut text = MultibyteText::default();
+    text.push_code(code)?;
+    Ok(cx.add(text))
 }
 
 #[defun]
@@ -152,25 +469,178 @@ mod test {
     use crate::{
         buffer::{get_buffer_create, set_buffer},
         core::gc::RootSet,
+        interpreter::assert_lisp,
     };
     use rune_core::macros::root;
 
     use super::*;
 
+    fn format_str(string: &str, objects: &[Object]) -> Result<String> {
+        Ok(format_text(string, objects)?.as_str().to_owned())
+    }
+
     #[test]
     fn test_format() {
-        assert_eq!(&format("%s", &[1.into()]).unwrap(), "1");
-        assert_eq!(&format("foo-%s", &[2.into()]).unwrap(), "foo-2");
-        assert_eq!(&format("%%", &[]).unwrap(), "%");
-        assert_eq!(&format("_%%_", &[]).unwrap(), "_%_");
-        assert_eq!(&format("foo-%s %s", &[3.into(), 4.into()]).unwrap(), "foo-3 4");
+        assert_eq!(&format_str("%s", &[1.into()]).unwrap(), "1");
+        assert_eq!(&format_str("foo-%s", &[2.into()]).unwrap(), "foo-2");
+        assert_eq!(&format_str("%%", &[]).unwrap(), "%");
+        assert_eq!(&format_str("_%%_", &[]).unwrap(), "_%_");
+        assert_eq!(&format_str("foo-%s %s", &[3.into(), 4.into()]).unwrap(), "foo-3 4");
         let sym = crate::core::env::sym::FUNCTION.into();
-        assert_eq!(&format("%s", &[sym]).unwrap(), "function");
+        assert_eq!(&format_str("%s", &[sym]).unwrap(), "function");
+
+        assert!(&format_str("%s", &[]).is_err());
+        assert!(&format_str("%s", &[1.into(), 2.into()]).is_err());
+
+        assert!(format_str("`%s' %s%s%s", &[0.into(), 1.into(), 2.into(), 3.into()]).is_ok());
+    }
 
-        assert!(&format("%s", &[]).is_err());
-        assert!(&format("%s", &[1.into(), 2.into()]).is_err());
+    #[test]
+    fn test_format_integer() {
+        assert_eq!(&format_str("%d", &[42.into()]).unwrap(), "42");
+        assert_eq!(&format_str("%05d", &[42.into()]).unwrap(), "00042");
+        assert_eq!(&format_str("%05d", &[(-42).into()]).unwrap(), "-0042");
+        assert_eq!(&format_str("%-5d|", &[42.into()]).unwrap(), "42   |");
+        assert_eq!(&format_str("%+d % d", &[42.into(), 42.into()]).unwrap(), "+42  42");
+        assert_eq!(&format_str("%.3d", &[7.into()]).unwrap(), "007");
+        assert_eq!(
+            &format_str("%x %X %o", &[255.into(), 255.into(), 8.into()]).unwrap(),
+            "ff FF 10"
+        );
+        assert_eq!(&format_str("%#x %#o", &[255.into(), 8.into()]).unwrap(), "0xff 010");
+        assert_eq!(&format_str("%#06x", &[255.into()]).unwrap(), "0x00ff");
+        assert_eq!(&format_str("%5s|%-5s|", &["ab".into(), "cd".into()]).unwrap(), "   ab|cd   |");
 
-        assert!(format("`%s' %s%s%s", &[0.into(), 1.into(), 2.into(), 3.into()]).is_ok());
+        let roots = &RootSet::default();
+        let cx = Context::new(roots);
+        assert_eq!(&format_str("%d", &[cx.add(2.9)]).unwrap(), "2");
+        assert_eq!(&format_str("%d", &[cx.add(-2.9)]).unwrap(), "-2");
+        assert!(&format_str("%d", &[cx.add("2")]).is_err());
+    }
+
+    #[test]
+    fn test_format_float() {
+        assert_lisp(r#"(format "%f" 1.5)"#, r#""1.500000""#);
+        assert_lisp(r#"(format "%.2f %.0f %#.0f" 2.675 2.5 2.0)"#, r#""2.67 2 2.""#);
+        assert_lisp(r#"(format "%e" 1234.5)"#, r#""1.234500e+03""#);
+        assert_lisp(r#"(format "%.1e %e" 0.00012 0)"#, r#""1.2e-04 0.000000e+00""#);
+        assert_lisp(r#"(format "%g %g %g" 100000 1000000 0.0001)"#, r#""100000 1e+06 0.0001""#);
+        assert_lisp(r#"(format "%g %.3g %#g" 1.5 3.14159 1.5)"#, r#""1.5 3.14 1.50000""#);
+        assert_lisp(r#"(format "%08.3f|%-8.2e|%+g" -1.5 1.5 2)"#, r#""-001.500|1.50e+00|+2""#);
+        assert_lisp(r#"(format "%f %5g" (/ 1.0 0.0) (/ -1.0 0.0))"#, r#""inf  -inf""#);
+        assert_lisp(r#"(format "%05.1f" (abs (/ 0.0 0.0)))"#, r#""  nan""#);
+    }
+
+    #[test]
+    fn test_format_errors() {
+        let not_enough = r#"(error "Not enough arguments for format string")"#;
+        assert_lisp_signal(r#"(format "%s")"#, not_enough);
+        assert_lisp_signal(r#"(format "%d %d" 1)"#, not_enough);
+        assert_lisp_signal(r#"(format "%% %s")"#, not_enough);
+        let mismatch = r#"(error "Format specifier doesn't match argument type")"#;
+        assert_lisp_signal(r#"(format "%d" "1")"#, mismatch);
+        assert_lisp_signal(r#"(format "%x" 'foo)"#, mismatch);
+        assert_lisp_signal(r#"(format "%c" "a")"#, mismatch);
+        assert_lisp_signal(r#"(format "%c" -1)"#, mismatch);
+        assert_lisp_signal(r#"(format "%f" "1.5")"#, mismatch);
+        assert_lisp(r#"(format "%c%3c" ?a ?λ)"#, r#""a  λ""#);
+    }
+
+    #[test]
+    fn test_format_field_numbers() {
+        assert_lisp(r#"(format "%2$s %1$s" "a" "b")"#, r#""b a""#);
+        assert_lisp(r#"(format "%1$s %1$S" "a")"#, r#""a \"a\"""#);
+        assert_lisp(r#"(format "%2$d-%2$x" 1 255)"#, r#""255-ff""#);
+        // flags, width, and precision come after the field number
+        assert_lisp(r#"(format "%2$-4d|%1$05d" 7 42)"#, r#""42  |00007""#);
+        assert_lisp(r#"(format "%1$.2s" "abc")"#, r#""ab""#);
+        // unused numbered arguments are fine
+        assert_lisp(r#"(format "%2$s" "a" "b")"#, r#""b""#);
+        assert_lisp(r#"(format "%% %1$s" 1)"#, r#""% 1""#);
+        let mixed = r#"(error "Format string mixes numbered and unnumbered arguments")"#;
+        assert_lisp_signal(r#"(format "%1$s %s" 1 2)"#, mixed);
+        assert_lisp_signal(r#"(format "%s %1$s" 1)"#, mixed);
+        let not_enough = r#"(error "Not enough arguments for format string")"#;
+        assert_lisp_signal(r#"(format "%3$s" 1 2)"#, not_enough);
+        let invalid = r#"(error "Invalid format field number")"#;
+        assert_lisp_signal(r#"(format "%0$s" 1)"#, invalid);
+        assert_lisp_signal(r#"(format "%$s" 1)"#, invalid);
+    }
+
+    #[test]
+    fn test_format_readably() {
+        assert_lisp(r#"(format "%s" "a\"b")"#, r#""a\"b""#);
+        assert_lisp(r#"(format "%S" "a\"b")"#, r#""\"a\\\"b\"""#);
+        assert_lisp(r#"(format "%S" "a\\b")"#, r#""\"a\\\\b\"""#);
+        assert_lisp(r#"(format "%S" '(1 "x" foo))"#, r#""(1 \"x\" foo)""#);
+        assert_lisp(r#"(format "%S %s" 1.5 1.5)"#, r#""1.5 1.5""#);
+        assert_lisp(r#"(format "%S" 42)"#, r#""42""#);
+        assert_lisp(r#"(format "%5S|" 'ab)"#, r#""   ab|""#);
+    }
+
+    #[test]
+    fn test_format_bignum() {
+        assert_lisp("(format \"%x\" (expt 2 70))", "\"400000000000000000\"");
+        assert_lisp("(format \"%d\" (expt 2 70))", "\"1180591620717411303424\"");
+        assert_lisp("(format \"%o\" (expt -2 65))", "\"-4000000000000000000000\"");
+    }
+
+    #[test]
+    fn test_format_huge_bignum() {
+        // 3^10000 has 4772 digits
+        let ends = "(list (length s) (substring s 0 20) (substring s -20))";
+        assert_lisp(
+            &format!("(let ((s (format \"%d\" (expt 3 10000)))) {ends})"),
+            "(4772 \"16313501853426258743\" \"41498105206552200001\")",
+        );
+        assert_lisp("(length (format \"%d\" (- (expt 3 10000))))", "4773");
+        let same = "(equal (number-to-string (expt 3 10000)) (format \"%d\" (expt 3 10000)))";
+        assert_lisp(same, "t");
+        let power_of_two = |conversion, exp, zeros| {
+            let form = format!("(format \"%{conversion}\" (expt 2 {exp}))");
+            format!("(equal {form} (concat \"1\" (make-string {zeros} ?0)))")
+        };
+        assert_lisp(&power_of_two('x', 10000, 2500), "t");
+        assert_lisp(&power_of_two('X', 10000, 2500), "t");
+        assert_lisp(&power_of_two('o', 9999, 3333), "t");
+    }
+
+    #[test]
+    fn test_char_equal() {
+        assert_lisp("(char-equal ?a ?a)", "t");
+        assert_lisp("(char-equal ?a ?b)", "nil");
+        assert_lisp("(char-equal ?A ?a)", "t");
+        assert_lisp("(char-equal ?Σ ?σ)", "t");
+        assert_lisp("(let ((case-fold-search nil)) (char-equal ?A ?a))", "nil");
+        assert_lisp("(let ((case-fold-search nil)) (char-equal ?a ?a))", "t");
+        assert_lisp("(let ((case-fold-search t)) (char-equal ?A ?a))", "t");
+    }
+
+    #[test]
+    fn test_char_equal_simple_folding() {
+        // these fold together under simple case folding
+        assert_lisp("(char-equal ?ς ?σ)", "t");
+        assert_lisp("(char-equal ?ς ?Σ)", "t");
+        assert_lisp("(char-equal ?ſ ?s)", "t");
+        assert_lisp("(char-equal ?\\u212A ?k)", "t");
+        assert_lisp("(char-equal ?ẞ ?ß)", "t");
+        assert_lisp("(char-equal ?ǅ ?ǆ)", "t");
+        // but these only fold together under full or Turkic folding
+        assert_lisp("(char-equal ?ß ?s)", "nil");
+        assert_lisp("(char-equal ?İ ?i)", "nil");
+        assert_lisp("(char-equal ?ı ?i)", "nil");
+        assert_lisp("(char-equal ?ı ?I)", "nil");
+        assert_lisp("(char-equal ?ﬁ ?f)", "nil");
+        // the same chars match in case-insensitive string comparisons
+        let compare = |s1, s2| format!("(compare-strings \"{s1}\" nil nil \"{s2}\" nil nil t)");
+        assert_lisp(&compare("ſς", "SΣ"), "t");
+        assert_lisp(&compare("ẞ", "ß"), "t");
+        assert_lisp(&compare("ß", "ss"), "1");
+        assert_lisp(&compare("ﬁ", "fi"), "1");
+        assert_lisp(&compare("İ", "i"), "1");
+        assert_lisp(&compare("ı", "i"), "1");
+        // and upcasing keeps the ordering of compare-strings
+        assert_lisp(&compare("a", "_"), "-1");
     }
 
     #[test]
@@ -190,6 +660,38 @@ mod test {
         assert_eq!(env.current_buffer.get(), "hello");
     }
 
+    #[test]
+    fn test_insert_unibyte() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        root!(env, new(Env), cx);
+        let buffer = get_buffer_create(cx.add("test_insert_unibyte"), Some(NIL), cx).unwrap();
+        set_buffer(buffer, env, cx).unwrap();
+        env.stack.push(cx.add(b"ab".to_vec()));
+        insert(ArgSlice::new(1), env, cx).unwrap();
+        assert_eq!(env.current_buffer.get(), "ab");
+        // buffers can't hold raw bytes, but the chars that stand in for them
+        // in strings are ordinary chars here
+        env.stack.push(cx.add(b"\xFF".to_vec()));
+        assert!(insert(ArgSlice::new(1), env, cx).is_err());
+        env.stack.push(cx.add(0x3F_FFFF));
+        assert!(insert(ArgSlice::new(1), env, cx).is_err());
+        env.stack.push(cx.add(0x10_FFFF));
+        insert(ArgSlice::new(1), env, cx).unwrap();
+        assert_eq!(env.current_buffer.get(), "ab\u{10FFFF}");
+        assert_lisp("(progn (insert (string ?a) (char-to-string ?b)) (point-max))", "3");
+        assert_lisp("(progn (insert (string #x10FFFF ?λ)) (point-max))", "3");
+    }
+
+    #[test]
+    fn test_format_unibyte() {
+        assert_lisp(r#"(format "%s" (string ?a))"#, r#""a""#);
+        assert_lisp(r#"(format "%s-%s" (char-to-string ?a) (string ?b ?c))"#, r#""a-bc""#);
+        assert_lisp(r#"(format "%S" (string ?a))"#, r#""\"a\"""#);
+        assert_lisp(r#"(format "%3s|" (string ?a))"#, r#""  a|""#);
+        assert_lisp(r#"(mapconcat #'identity (list (string ?a) "b") (string ?-))"#, r#""a-b""#);
+    }
+
     #[test]
     fn test_delete_region() {
         let roots = &RootSet::default();
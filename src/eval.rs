@@ -3,9 +3,7 @@ use crate::core::cons::{Cons, ConsError};
 use crate::core::env::{ArgSlice, CallFrame, Env, sym};
 use crate::core::error::{Type, TypeError};
 use crate::core::gc::{Rt, Rto};
-use crate::core::object::{
-    FnArgs, Function, LispString, NIL, ObjectType, Symbol, TagType, display_slice,
-};
+use crate::core::object::{FnArgs, Function, NIL, ObjectType, Symbol, TagType, display_slice};
 use crate::core::{
     gc::Context,
     object::{FunctionType, Gc, Object},
@@ -252,8 +250,8 @@ pub(crate) fn autoload_do_load<'ob>(
     };
     ensure!(macro_only.is_none(), "autoload-do-load macro-only is not yet implemented");
     let mut iter = body.elements();
-    let file: Gc<&LispString> = match iter.next() {
-        Some(x) => x?.try_into()?,
+    let file = match iter.next() {
+        Some(x) => x?,
         None => bail!("Malformed autoload"),
     };
     ensure!(
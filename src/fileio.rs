@@ -1,14 +1,18 @@
 //! File I/O.
-use crate::core::{
-    cons::Cons,
-    env::{Env, sym},
-    error::{Type, TypeError},
-    gc::{Context, Rt},
-    object::{Number, Object, ObjectType, OptionalFlag},
+use crate::{
+    character::string_text,
+    core::{
+        cons::Cons,
+        env::{Env, sym},
+        error::{Type, TypeError},
+        gc::{Context, Rt},
+        object::{Number, Object, ObjectType, OptionalFlag},
+    },
 };
 use anyhow::{Result, bail, ensure};
 use rune_macros::defun;
-use std::path::{Component, MAIN_SEPARATOR, Path};
+use std::borrow::Cow;
+use std::path::{Component, MAIN_SEPARATOR, MAIN_SEPARATOR_STR, Path};
 
 defvar!(FILE_NAME_HANDLER_ALIST);
 
@@ -19,25 +23,93 @@ pub(crate) fn expand_file_name(
     env: &Rt<Env>,
     cx: &Context,
 ) -> Result<String> {
-    // TODO: this needs to be tested to ensure it has the same behavior as GNU
-    // Emacs. It doesn't do any normalization for one thing.
-    if Path::new(name).is_absolute() {
-        Ok(name.to_owned())
-    } else if let Some(dir) = default_directory {
-        let path = Path::new(dir);
-        Ok(path.join(name).to_string_lossy().to_string())
+    let name = expand_home_dir(name);
+    if Path::new(&name).is_absolute() {
+        return Ok(normalize_file_name(&name));
+    }
+    let dir = match default_directory {
+        Some(dir) => Cow::Borrowed(dir),
+        // Like Emacs, fall back to the root when `default-directory' isn't a
+        // string
+        None => env
+            .vars
+            .get(sym::DEFAULT_DIRECTORY)
+            .and_then(|dir| string_text(dir.bind(cx)))
+            .unwrap_or(Cow::Borrowed(MAIN_SEPARATOR_STR)),
+    };
+    let dir = expand_home_dir(&dir);
+    // Like Emacs, a relative directory is first expanded against
+    // `default-directory', and that one against the root
+    let mut path = if Path::new(&dir).is_absolute() {
+        dir
+    } else if default_directory.is_some() {
+        expand_file_name(&dir, None, env, cx)?
     } else {
-        let dir = env.vars.get(sym::DEFAULT_DIRECTORY).unwrap();
-        match dir.untag(cx) {
-            ObjectType::String(dir) => {
-                let path = Path::new(dir.as_ref());
-                Ok(path.join(name).to_string_lossy().to_string())
-            }
-            _ => unreachable!("`default-directory' should be a string"),
+        expand_file_name(&dir, Some(MAIN_SEPARATOR_STR), env, cx)?
+    };
+    // An empty name is the directory itself, without a trailing separator
+    if name.is_empty() {
+        let mut dir = normalize_file_name(&path);
+        if dir.len() > 1 && dir.ends_with(MAIN_SEPARATOR) {
+            dir.pop();
         }
+        return Ok(dir);
+    }
+    if !path.is_empty() && !path.ends_with(MAIN_SEPARATOR) {
+        path.push(MAIN_SEPARATOR);
+    }
+    path.push_str(&name);
+    Ok(normalize_file_name(&path))
+}
+
+/// Replace a leading `~` with the home directory of the current user.
+fn expand_home_dir(name: &str) -> String {
+    replace_home_dir(name, std::env::var("HOME").ok().as_deref())
+}
+
+/// Replace a leading `~` in `name` with `home`, if there is one.
+fn replace_home_dir(name: &str, home: Option<&str>) -> String {
+    let Some(rest) = name.strip_prefix('~') else { return name.to_owned() };
+    // TODO: expand `~user' to the home directory of that user
+    if !(rest.is_empty() || rest.starts_with(MAIN_SEPARATOR)) {
+        return name.to_owned();
+    }
+    match home {
+        Some(home) => format!("{home}{rest}"),
+        None => name.to_owned(),
     }
 }
 
+/// Collapse `.` and `..` components and repeated separators, keeping a
+/// trailing separator if one was present.
+fn normalize_file_name(name: &str) -> String {
+    let absolute = name.starts_with(MAIN_SEPARATOR);
+    let mut components: Vec<&str> = Vec::new();
+    for component in name.split(MAIN_SEPARATOR) {
+        match component {
+            "" | "." => {}
+            ".." => match components.last() {
+                Some(&last) if last != ".." => {
+                    components.pop();
+                }
+                // The parent of root is root
+                _ if absolute => {}
+                _ => components.push(".."),
+            },
+            component => components.push(component),
+        }
+    }
+    let mut normalized = String::with_capacity(name.len());
+    if absolute {
+        normalized.push(MAIN_SEPARATOR);
+    }
+    normalized.push_str(&components.join(MAIN_SEPARATOR_STR));
+    if !components.is_empty() && name.ends_with(MAIN_SEPARATOR) {
+        normalized.push(MAIN_SEPARATOR);
+    }
+    normalized
+}
+
 #[defun]
 fn car_less_than_car(a: &Cons, b: &Cons) -> Result<bool> {
     let a: Number = a.car().try_into()?;
@@ -91,38 +163,17 @@ fn file_name_absolute_p(filename: &str) -> bool {
 fn file_name_directory(filename: &str) -> Option<String> {
     // TODO: GNU Emacs docs stipulate that "On MS-DOS [ed: presumably windows,
     // too] it can also end in a colon."
-    if !filename.contains(MAIN_SEPARATOR) {
-        return None;
-    }
-
-    if filename.ends_with(MAIN_SEPARATOR) {
-        return Some(filename.into());
-    }
-
-    let path = Path::new(filename);
-    let parent = path.parent()?;
-
-    // Special case for root path so we don't end up returning '//'
-    if parent.parent().is_none() {
-        return Some(format!("{MAIN_SEPARATOR}"));
-    }
-    let parent_path = parent.to_str()?;
-    Some(format!("{parent_path}{MAIN_SEPARATOR}"))
+    let idx = filename.rfind(MAIN_SEPARATOR)?;
+    Some(filename[..=idx].to_owned())
 }
 
 /// Returns the non-directory part of `filename`
 #[defun]
-fn file_name_nondirectory(filename: &str) -> &str {
-    if filename.ends_with(MAIN_SEPARATOR) {
-        return "";
+fn file_name_nondirectory(filename: &str) -> String {
+    match filename.rfind(MAIN_SEPARATOR) {
+        Some(idx) => filename[idx + 1..].to_owned(),
+        None => filename.to_owned(),
     }
-
-    let path = Path::new(filename);
-    let Some(file_name) = path.file_name() else {
-        return "";
-    };
-
-    file_name.to_str().unwrap()
 }
 
 /// Return non-nil if NAME ends with a directory separator character.
@@ -228,7 +279,7 @@ fn file_name_concat(directory: &str, rest_components: &[Object]) -> Result<Strin
 
     // All components must be stringp...
     for r_c in rest_components {
-        let ObjectType::String(s) = r_c.untag() else {
+        let Some(s) = string_text(*r_c) else {
             bail!(TypeError::new(Type::String, r_c));
         };
 
@@ -238,7 +289,7 @@ fn file_name_concat(directory: &str, rest_components: &[Object]) -> Result<Strin
             path.push(MAIN_SEPARATOR)
         }
 
-        path.push_str(s.as_ref());
+        path.push_str(&s);
     }
 
     Ok(path)
@@ -248,3 +299,65 @@ fn file_name_concat(directory: &str, rest_components: &[Object]) -> Result<Strin
 // TODO: file-name-sans-versions
 // TODO: find-file-name-handler: https://www.gnu.org/software/emacs/manual/html_node/elisp/Magic-File-Names.html
 //   required by file-name-extension  & file-name-sans-extension library & file-relative-name functions (among others)
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interpreter::assert_lisp;
+
+    #[test]
+    fn test_expand_file_name() {
+        assert_lisp("(expand-file-name \"foo\" \"/tmp\")", "\"/tmp/foo\"");
+        assert_lisp("(expand-file-name \"foo\" \"/tmp/\")", "\"/tmp/foo\"");
+        assert_lisp("(expand-file-name \"./foo//bar/\" \"/tmp\")", "\"/tmp/foo/bar/\"");
+        assert_lisp("(expand-file-name \"/a/b\" \"/tmp\")", "\"/a/b\"");
+        assert_lisp("(expand-file-name \"\" \"/tmp/\")", "\"/tmp\"");
+        assert_lisp("(expand-file-name \"\" \"/\")", "\"/\"");
+    }
+
+    #[test]
+    fn test_expand_file_name_parent() {
+        assert_lisp("(expand-file-name \"foo/../bar\" \"/tmp\")", "\"/tmp/bar\"");
+        assert_lisp("(expand-file-name \"../..\" \"/a/b/c\")", "\"/a\"");
+        assert_lisp("(expand-file-name \"/a/./b/../c\")", "\"/a/c\"");
+        assert_lisp("(expand-file-name \"/../..\")", "\"/\"");
+        assert_eq!(normalize_file_name("../a/../../b"), "../../b");
+    }
+
+    #[test]
+    fn test_expand_file_name_home() {
+        let home = Some("/home/user");
+        assert_eq!(replace_home_dir("~", home), "/home/user");
+        assert_eq!(replace_home_dir("~/foo", home), "/home/user/foo");
+        assert_eq!(replace_home_dir("~user/foo", home), "~user/foo");
+        assert_eq!(replace_home_dir("foo/~", home), "foo/~");
+        assert_eq!(replace_home_dir("~/foo", None), "~/foo");
+        assert_lisp("(expand-file-name \"~user/foo\" \"/tmp\")", "\"/tmp/~user/foo\"");
+    }
+
+    #[test]
+    fn test_expand_file_name_relative_directory() {
+        let expand = |form| format!("(let ((default-directory \"/tmp/\")) {form})");
+        assert_lisp(&expand("(expand-file-name \"foo\" \"bar\")"), "\"/tmp/bar/foo\"");
+        assert_lisp(&expand("(expand-file-name \"foo\" \"../bar\")"), "\"/bar/foo\"");
+        let relative = "(let ((default-directory \"rel/\")) (expand-file-name \"foo\"))";
+        assert_lisp(relative, "\"/rel/foo\"");
+        let not_string = "(let ((default-directory nil)) (expand-file-name \"foo\"))";
+        assert_lisp(not_string, "\"/foo\"");
+    }
+
+    #[test]
+    fn test_file_name_directory() {
+        assert_lisp("(file-name-directory \"/a/b/c\")", "\"/a/b/\"");
+        assert_lisp("(file-name-directory \"/a/b/\")", "\"/a/b/\"");
+        assert_lisp("(file-name-directory \"/\")", "\"/\"");
+        assert_lisp("(file-name-directory \"c\")", "nil");
+    }
+
+    #[test]
+    fn test_file_name_nondirectory() {
+        assert_lisp("(file-name-nondirectory \"/a/b/c\")", "\"c\"");
+        assert_lisp("(file-name-nondirectory \"/a/b/\")", "\"\"");
+        assert_lisp("(file-name-nondirectory \"c\")", "\"c\"");
+    }
+}
@@ -2,18 +2,21 @@
 use std::ops::{AddAssign, BitAnd, Div, Rem, SubAssign};
 
 use crate::{
-    arith::NumberValue,
+    arith::{MAX_FIXNUM, MIN_FIXNUM, NumberValue, big_to_f64, exact_number, ratio_to_f64},
     core::{
         cons::Cons,
-        gc::Context,
-        object::{Number, NumberType, Object},
+        env::{Env, sym},
+        gc::{Context, Rt},
+        object::{Number, NumberType, Object, ObjectType},
     },
+    data::LispError,
 };
 use anyhow::Result;
-use anyhow::anyhow;
+use anyhow::{anyhow, bail, ensure};
 use num_bigint::BigInt;
 use num_integer::Integer;
-use num_traits::{FromPrimitive, Signed, ToPrimitive, Zero};
+use num_rational::BigRational;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
 
 use rune_macros::defun;
 
@@ -22,7 +25,16 @@ fn coerce(arg: Number) -> f64 {
     match arg.untag() {
         NumberType::Int(i) => i as f64,
         NumberType::Float(f) => **f,
-        NumberType::Big(b) => b.to_f64().unwrap(), // TODO: Handle big integers
+        NumberType::Big(b) => big_to_f64(b),
+    }
+}
+
+fn coerce_value(arg: &NumberValue) -> f64 {
+    match arg {
+        NumberValue::Int(i) => *i as f64,
+        NumberValue::Float(f) => *f,
+        NumberValue::Big(b) => big_to_f64(b),
+        NumberValue::Ratio(r) => ratio_to_f64(r),
     }
 }
 
@@ -55,6 +67,7 @@ fn rescale_for_division(n: NumberValue, nscale: i64, dscale: i64) -> Result<BigI
             BigInt::from_f64(libm::scalbn(f, nscale as i32)).expect("Conversion error")
         }
         NumberValue::Big(b) => b,
+        NumberValue::Ratio(_) => unreachable!("Lisp numbers are never ratios"),
     };
 
     if nscale < dscale {
@@ -65,29 +78,120 @@ fn rescale_for_division(n: NumberValue, nscale: i64, dscale: i64) -> Result<BigI
     Ok(result)
 }
 
+/// How to round a number to an integral value. The `F` variants keep the
+/// result as a float, like `ffloor`, instead of converting it to an integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RoundMode {
+    Floor,
+    Ceiling,
+    Round,
+    Truncate,
+    FFloor,
+    FCeiling,
+    FRound,
+    FTruncate,
+}
+
+impl RoundMode {
+    fn round_float(self, x: f64) -> f64 {
+        match self {
+            Self::Floor | Self::FFloor => x.floor(),
+            Self::Ceiling | Self::FCeiling => x.ceil(),
+            // Emacs rounds halfway cases to even
+            Self::Round | Self::FRound => x.round_ties_even(),
+            Self::Truncate | Self::FTruncate => x.trunc(),
+        }
+    }
+
+    fn keeps_float(self) -> bool {
+        matches!(self, Self::FFloor | Self::FCeiling | Self::FRound | Self::FTruncate)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Floor => "floor",
+            Self::Ceiling => "ceiling",
+            Self::Round => "round",
+            Self::Truncate => "truncate",
+            Self::FFloor => "ffloor",
+            Self::FCeiling => "fceiling",
+            Self::FRound => "fround",
+            Self::FTruncate => "ftruncate",
+        }
+    }
+}
+
+impl NumberValue {
+    /// Round to an integral value using `mode`. Integers are already integral,
+    /// so they are only converted to floats for the `F` modes.
+    pub(crate) fn round_to(self, mode: RoundMode) -> Result<NumberValue> {
+        match self {
+            NumberValue::Float(x) => {
+                let rounded = mode.round_float(x);
+                if mode.keeps_float() {
+                    Ok(NumberValue::Float(rounded))
+                } else if rounded.is_finite() {
+                    Ok(NumberValue::Float(rounded).coerce_integer())
+                } else {
+                    Err(anyhow!("{x} can't be rounded to an integer"))
+                }
+            }
+            int if mode.keeps_float() => Ok(NumberValue::Float(coerce_value(&int))),
+            int => Ok(int),
+        }
+    }
+}
+
 fn rounding_driver(
     n: NumberValue,
     d: Option<NumberValue>,
-    double_round: fn(f64) -> f64,
+    mode: RoundMode,
     int_divide: fn(i64, i64) -> i64,
     bigum_divide: fn(BigInt, BigInt) -> BigInt,
+    cx: &Context,
 ) -> Result<NumberValue> {
+    let name = mode.name();
     let d = match d {
         None => {
-            return Ok(match n {
-                NumberValue::Float(f) => NumberValue::Float(double_round(f)).coerce_integer(),
-                other => other,
-            });
+            return match n {
+                // NaN and infinity have no integer value
+                NumberValue::Float(f) if !f.is_finite() => {
+                    Err(LispError::range_error(name, f, cx).into())
+                }
+                n => n.round_to(mode),
+            };
+        }
+        Some(NumberValue::Float(d)) if d == 0.0 => {
+            // The quotient is infinite (or NaN for 0/0), so it can't be rounded
+            let quotient = coerce_value(&n) / d;
+            return Err(LispError::range_error(name, quotient, cx).into());
         }
         Some(d) if d.is_zero() => {
-            return Err(anyhow!("(arith-error)"));
+            return Err(LispError::arith_error(cx).into());
         }
         Some(d) => d,
     };
 
     match (n, d) {
-        (NumberValue::Int(n), NumberValue::Int(d)) => Ok(NumberValue::Int(int_divide(n, d))),
+        (NumberValue::Int(n), NumberValue::Int(d)) => {
+            // most-negative-fixnum divided by -1 is the only quotient that
+            // is not a fixnum
+            let quotient = int_divide(n, d);
+            if (MIN_FIXNUM..=MAX_FIXNUM).contains(&quotient) {
+                Ok(NumberValue::Int(quotient))
+            } else {
+                Ok(NumberValue::Big(quotient.into()))
+            }
+        }
         (n, d) => {
+            // Dividing NaN or infinity can't give an integer quotient
+            let non_finite = matches!(n, NumberValue::Float(f) if !f.is_finite())
+                || matches!(d, NumberValue::Float(f) if f.is_nan());
+            if non_finite {
+                let quotient = coerce_value(&n) / coerce_value(&d);
+                return Err(LispError::range_error(name, quotient, cx).into());
+            }
+
             let dscale = match d {
                 NumberValue::Float(f) => double_integer_scale(f),
                 _ => 0,
@@ -158,76 +262,113 @@ fn checked_pow(base: BigInt, exp: u32) -> Result<BigInt> {
     if n > LIMBS_LIMIT {
         return Err(anyhow!("Overflow error".to_string()));
     }
+    // A power of two base can be raised with a single shift
+    let magnitude = base.magnitude();
+    if magnitude.count_ones() == 1 {
+        let result = BigInt::one() << ((magnitude.bits() - 1) * u64::from(exp));
+        return Ok(if base.is_negative() && exp % 2 == 1 { -result } else { result });
+    }
     Ok(base.pow(exp))
 }
 
 #[defun]
-fn floor(num: Number, divisor: Option<Number>) -> Result<NumberValue> {
+fn floor(num: Number, divisor: Option<Number>, cx: &Context) -> Result<NumberValue> {
     rounding_driver(
         num.val(),
         divisor.map(|d| d.val()),
-        |f| f.floor(),
+        RoundMode::Floor,
         |n, d| num_integer::Integer::div_floor(&n, &d),
         |n, d| n.div_floor(&d),
+        cx,
     )
 }
 
 #[defun]
-fn ceiling(num: Number, divisor: Option<Number>) -> Result<NumberValue> {
+fn ceiling(num: Number, divisor: Option<Number>, cx: &Context) -> Result<NumberValue> {
     rounding_driver(
         num.val(),
         divisor.map(|d| d.val()),
-        |f| f.ceil(),
+        RoundMode::Ceiling,
         |n, d| num_integer::Integer::div_ceil(&n, &d),
         |n, d| n.div_ceil(&d),
+        cx,
     )
 }
 
 #[defun]
-fn round(num: Number, divisor: Option<Number>) -> Result<NumberValue> {
+fn round(num: Number, divisor: Option<Number>, cx: &Context) -> Result<NumberValue> {
     rounding_driver(
         num.val(),
         divisor.map(|d| d.val()), //
-        |f| f.round(),
+        RoundMode::Round,
         round2,
         round2,
+        cx,
     )
 }
 
 #[defun]
-fn truncate(num: Number, divisor: Option<Number>) -> Result<NumberValue> {
+fn truncate(num: Number, divisor: Option<Number>, cx: &Context) -> Result<NumberValue> {
     rounding_driver(
         num.val(),
         divisor.map(|d| d.val()), //
-        |f| f,
+        RoundMode::Truncate,
         |n, d| n.div(&d),
         |n, d| n.div(&d),
+        cx,
     )
 }
 
+/// The quotient of Common Lisp's `truncate`, without the remainder. This
+/// always rounds toward zero, so it agrees with `truncate` for every argument.
+#[defun]
+fn cl_truncate(x: Number, divisor: Option<Number>, cx: &Context) -> Result<NumberValue> {
+    truncate(x, divisor, cx)
+}
+
+/// The exact value of `x` as a reduced `(NUMERATOR . DENOMINATOR)` pair, the
+/// form `exact-divide` returns ratios in. Every finite float is a dyadic
+/// fraction, so the denominator of a float is always a power of two.
+#[defun(name = "cl-rational")]
+fn float_to_rational<'ob>(x: Object, cx: &'ob Context) -> Result<Object<'ob>> {
+    let ratio = match exact_number(x, cx)? {
+        NumberValue::Float(f) => match BigRational::from_float(f) {
+            Some(ratio) => ratio,
+            None => return Err(LispError::range_error("cl-rational", f, cx).into()),
+        },
+        NumberValue::Int(i) => BigRational::from_integer(i.into()),
+        NumberValue::Big(b) => BigRational::from_integer(b),
+        NumberValue::Ratio(ratio) => ratio,
+    };
+    let (numer, denom) = ratio.into_raw();
+    let numer = cx.add(NumberValue::Big(numer).normalize());
+    let denom = cx.add(NumberValue::Big(denom).normalize());
+    Ok(Cons::new(numer, denom, cx).into())
+}
+
 #[defun]
-fn fceiling(num: f64) -> f64 {
-    num.ceil()
+fn fceiling(num: f64) -> Result<NumberValue> {
+    NumberValue::Float(num).round_to(RoundMode::FCeiling)
 }
 
 #[defun]
-fn ffloor(num: f64) -> f64 {
-    num.floor()
+fn ffloor(num: f64) -> Result<NumberValue> {
+    NumberValue::Float(num).round_to(RoundMode::FFloor)
 }
 
 #[defun]
-fn fround(num: f64) -> f64 {
-    num.round()
+fn fround(num: f64) -> Result<NumberValue> {
+    NumberValue::Float(num).round_to(RoundMode::FRound)
 }
 
 #[defun]
-fn ftruncate(num: f64) -> f64 {
-    num.trunc()
+fn ftruncate(num: f64) -> Result<NumberValue> {
+    NumberValue::Float(num).round_to(RoundMode::FTruncate)
 }
 
 #[defun]
-fn float(arg: Number) -> NumberValue {
-    NumberValue::Float(coerce(arg))
+fn float(arg: Object, cx: &Context) -> Result<NumberValue> {
+    Ok(NumberValue::Float(coerce_value(&exact_number(arg, cx)?)))
 }
 
 #[defun]
@@ -278,12 +419,23 @@ fn exp(arg: Number) -> f64 {
     coerce(arg).exp()
 }
 
+defvar!(INTEGER_WIDTH, 65536);
+
+/// The largest number of bits an integer result can have, from
+/// `integer-width`.
+pub(crate) fn integer_width(env: &Rt<Env>, cx: &Context) -> u64 {
+    match env.vars.get(sym::INTEGER_WIDTH).map(|x| x.untag(cx)) {
+        Some(ObjectType::Int(width)) if width >= 0 => width as u64,
+        _ => 65536,
+    }
+}
+
 #[defun]
-fn expt(x: Number, y: Number) -> NumberValue {
+fn expt(x: Number, y: Number, env: &Rt<Env>, cx: &Context) -> Result<NumberValue> {
     // If either is a float, we use the float version
     match (x.untag(), y.untag()) {
         (NumberType::Float(_), _) | (_, NumberType::Float(_)) => {
-            return NumberValue::Float(coerce(x).powf(coerce(y)));
+            return Ok(NumberValue::Float(coerce(x).powf(coerce(y))));
         }
         (_, _) => {}
     };
@@ -293,23 +445,41 @@ fn expt(x: Number, y: Number) -> NumberValue {
         NumberType::Int(x) => BigInt::from(x),
         NumberType::Big(b) => (*b).clone(),
         NumberType::Float(x) => {
-            return NumberValue::Float(x.powf(coerce(y)));
+            return Ok(NumberValue::Float(x.powf(coerce(y))));
         }
     };
 
     let by = match y.untag() {
-        NumberType::Int(x) => u32::try_from(x).ok(),
-        NumberType::Big(b) => b.to_u32(),
+        NumberType::Int(x) => BigInt::from(x),
+        NumberType::Big(b) => (*b).clone(),
         NumberType::Float(y) => {
-            return NumberValue::Float(coerce(x).powf(**y));
+            return Ok(NumberValue::Float(coerce(x).powf(**y)));
         }
     };
 
-    if let Some(y) = by {
-        let result = checked_pow(bx, y).unwrap();
-        return NumberValue::Big(result).coerce_integer();
+    if by.is_negative() {
+        return Ok(NumberValue::Float(coerce(x).powf(coerce(y))));
+    }
+    // 0, 1 and -1 stay small no matter how large the exponent is
+    if bx.magnitude().is_zero() || bx.magnitude().is_one() {
+        let result = if bx.is_zero() && !by.is_zero() {
+            0
+        } else if bx.is_negative() && by.is_odd() {
+            -1
+        } else {
+            1
+        };
+        return Ok(NumberValue::Int(result));
     }
-    NumberValue::Float(coerce(x).powf(coerce(y)))
+    // Any other base at least doubles with each power, so check the size of
+    // the result before trying to allocate it.
+    let max_bits = integer_width(env, cx);
+    let Some(y) = by.to_u32().filter(|&y| (bx.bits() - 1) * u64::from(y) < max_bits) else {
+        bail!("Integer too large")
+    };
+    let result = checked_pow(bx, y)?;
+    ensure!(result.bits() <= max_bits, "Integer too large");
+    Ok(NumberValue::Big(result).coerce_integer())
 }
 
 #[defun]
@@ -327,11 +497,14 @@ fn sqrt(arg: Number) -> f64 {
 }
 
 #[defun]
-fn abs(arg: Number) -> NumberValue {
-    match arg.untag() {
-        NumberType::Int(i) => NumberValue::Int(i.abs()),
-        NumberType::Float(f) => NumberValue::Float(f.abs()),
-        NumberType::Big(b) => NumberValue::Big(b.abs()),
+fn abs<'ob>(arg: Number<'ob>, cx: &'ob Context) -> Object<'ob> {
+    match arg.val() {
+        // this also clears the sign of -0.0 and NaN
+        NumberValue::Float(f) if f.is_sign_negative() => cx.add(f.abs()),
+        // negating promotes the most negative fixnum to a bignum
+        n if n.is_negative() => cx.add(-n),
+        // anything else is unchanged, so it stays `eq` to the argument
+        _ => arg.into(),
     }
 }
 
@@ -341,10 +514,25 @@ fn ldexp(s: Number, e: i64) -> f64 {
 }
 
 #[defun]
-fn logb(arg: Number) -> i64 {
-    let l2 = coerce(arg).log2();
-    // Round down to an integer
-    l2.floor() as i64
+fn logb(arg: Number) -> NumberValue {
+    match arg.untag() {
+        NumberType::Int(0) => NumberValue::Float(f64::NEG_INFINITY),
+        NumberType::Int(i) => NumberValue::Int(i64::from(i.unsigned_abs().ilog2())),
+        NumberType::Big(b) => NumberValue::Int(b.bits() as i64 - 1),
+        NumberType::Float(f) => {
+            let f = **f;
+            if f == 0.0 {
+                NumberValue::Float(f64::NEG_INFINITY)
+            } else if f.is_infinite() {
+                NumberValue::Float(f64::INFINITY)
+            } else if f.is_nan() {
+                NumberValue::Float(f)
+            } else {
+                // frexp works on the magnitude, so negative numbers are handled
+                NumberValue::Int(frexp_f(f).1 - 1)
+            }
+        }
+    }
 }
 
 #[defun]
@@ -358,3 +546,258 @@ fn frexp_f(f: f64) -> (f64, i64) {
     let (x, exp) = libm::frexp(f);
     (x, exp as i64)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::gc::RootSet;
+    use crate::interpreter::assert_lisp;
+    use num_bigint::Sign;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_round_to() {
+        use NumberValue::{Float, Int};
+        let round = |x: f64, mode| Float(x).round_to(mode).unwrap();
+        assert_eq!(round(2.5, RoundMode::Floor), Int(2));
+        assert_eq!(round(-2.5, RoundMode::Floor), Int(-3));
+        assert_eq!(round(2.5, RoundMode::Ceiling), Int(3));
+        assert_eq!(round(-2.5, RoundMode::Ceiling), Int(-2));
+        assert_eq!(round(2.5, RoundMode::Round), Int(2));
+        assert_eq!(round(3.5, RoundMode::Round), Int(4));
+        assert_eq!(round(-2.5, RoundMode::Round), Int(-2));
+        assert_eq!(round(2.7, RoundMode::Truncate), Int(2));
+        assert_eq!(round(-2.7, RoundMode::Truncate), Int(-2));
+        assert_eq!(round(2.5, RoundMode::FFloor), Float(2.0));
+        assert_eq!(round(2.5, RoundMode::FCeiling), Float(3.0));
+        assert_eq!(round(2.5, RoundMode::FRound), Float(2.0));
+        assert_eq!(round(-2.7, RoundMode::FTruncate), Float(-2.0));
+        assert_eq!(round(1e20, RoundMode::Floor), NumberValue::Big(BigInt::from(10).pow(20)));
+        assert_eq!(Int(7).round_to(RoundMode::Round).unwrap(), Int(7));
+        assert_eq!(Int(7).round_to(RoundMode::FRound).unwrap(), Float(7.0));
+        assert!(Float(f64::NAN).round_to(RoundMode::Floor).is_err());
+        assert!(Float(f64::INFINITY).round_to(RoundMode::FFloor).is_ok());
+    }
+
+    #[test]
+    fn test_pow_power_of_two() {
+        for base in [1, 2, 4, 8, -1, -2, -4] {
+            for exp in [0, 1, 2, 3, 63, 64, 100, 1001] {
+                let base = BigInt::from(base);
+                assert_eq!(checked_pow(base.clone(), exp).unwrap(), base.pow(exp));
+            }
+        }
+        assert_lisp("(expt 2 100)", "1267650600228229401496703205376");
+        assert_lisp("(expt 4 3)", "64");
+        assert_lisp("(expt -2 3)", "-8");
+        assert_lisp("(= (expt 2 60000) (expt 4 30000))", "t");
+        assert_lisp("(= (expt 3 5) 243)", "t");
+    }
+
+    #[test]
+    fn test_expt_too_large() {
+        let too_large = |form| format!("(condition-case err {form} (error (cadr err)))");
+        assert_lisp(&too_large("(expt 2 (expt 2 100))"), "\"Integer too large\"");
+        assert_lisp(&too_large("(expt 3 100000)"), "\"Integer too large\"");
+        assert_lisp(&too_large("(expt (expt 2 100) 1000)"), "\"Integer too large\"");
+        assert_lisp("(let ((integer-width 200)) (expt 2 100))", "1267650600228229401496703205376");
+        assert_lisp(
+            &too_large("(let ((integer-width 100)) (expt 2 100))"),
+            "\"Integer too large\"",
+        );
+        // these never grow, so any exponent is fine
+        assert_lisp("(expt 1 (expt 2 100))", "1");
+        assert_lisp("(expt -1 (expt 2 100))", "1");
+        assert_lisp("(expt -1 (1+ (expt 2 100)))", "-1");
+        assert_lisp("(expt 0 (expt 2 100))", "0");
+        assert_lisp("(expt 0 0)", "1");
+    }
+
+    fn float_then_truncate(n: NumberValue) -> NumberValue {
+        NumberValue::Float(coerce_value(&n)).round_to(RoundMode::Truncate).unwrap()
+    }
+
+    proptest! {
+        // Every integer up to 2^53 has an exact float, so the round trip is
+        // lossless.
+        #[test]
+        fn float_truncate_exact(n in -(1_i64 << 53)..=(1_i64 << 53)) {
+            prop_assert_eq!(float_then_truncate(NumberValue::Int(n)), NumberValue::Int(n));
+        }
+
+        // Fixnums go up to 2^55, and past 2^53 the float is only the nearest
+        // representable value. The round trip is then off by at most half an
+        // ulp.
+        #[test]
+        fn float_truncate_fixnum(n in MIN_FIXNUM..=MAX_FIXNUM) {
+            let result = match float_then_truncate(NumberValue::Int(n)) {
+                NumberValue::Int(x) => i128::from(x),
+                NumberValue::Big(x) => x.to_i128().unwrap(),
+                x => panic!("truncate returned {x:?}"),
+            };
+            let bits = 64 - n.unsigned_abs().leading_zeros();
+            let half_ulp = 1_u128 << bits.saturating_sub(54);
+            prop_assert!((result - i128::from(n)).unsigned_abs() <= half_ulp);
+        }
+
+        // Bignums with at most 53 significant bits are exact floats as well.
+        #[test]
+        fn float_truncate_bignum(mantissa in -(1_i64 << 53)..=(1_i64 << 53), shift in 0_u32..960) {
+            let n = NumberValue::Big(BigInt::from(mantissa) << shift).coerce_integer();
+            prop_assert_eq!(float_then_truncate(n.clone()), n);
+        }
+    }
+
+    fn integer() -> impl Strategy<Value = BigInt> {
+        let sign = |negative| if negative { Sign::Minus } else { Sign::Plus };
+        prop_oneof![
+            any::<i64>().prop_map(BigInt::from),
+            (any::<bool>(), prop::collection::vec(any::<u32>(), 1..6))
+                .prop_map(move |(negative, digits)| BigInt::new(sign(negative), digits)),
+        ]
+    }
+
+    fn divide(x: &BigInt, y: &BigInt, mode: RoundMode) -> NumberValue {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        let number = |x: &BigInt| -> Number {
+            cx.add(NumberValue::Big(x.clone()).coerce_integer()).try_into().unwrap()
+        };
+        let (x, y) = (number(x), Some(number(y)));
+        match mode {
+            RoundMode::Floor => floor(x, y, cx),
+            RoundMode::Ceiling => ceiling(x, y, cx),
+            RoundMode::Round => round(x, y, cx),
+            RoundMode::Truncate => truncate(x, y, cx),
+            _ => unreachable!(),
+        }
+        .unwrap()
+    }
+
+    proptest! {
+        // The remainder is whatever makes `x == y*q + r` hold, so what each
+        // mode has to get right is the size and sign of the remainder.
+        #[test]
+        fn division_remainder(x in integer(), y in integer().prop_filter("zero", |y| !y.is_zero()))
+        {
+            use RoundMode::{Ceiling, Floor, Round, Truncate};
+            for mode in [Floor, Ceiling, Truncate, Round] {
+                let q = match divide(&x, &y, mode) {
+                    NumberValue::Int(q) => {
+                        prop_assert!((MIN_FIXNUM..=MAX_FIXNUM).contains(&q));
+                        BigInt::from(q)
+                    }
+                    NumberValue::Big(q) => {
+                        let fixnums = MIN_FIXNUM..=MAX_FIXNUM;
+                        prop_assert!(!q.to_i64().is_some_and(|q| fixnums.contains(&q)));
+                        q
+                    }
+                    q => panic!("{mode:?} returned {q:?}"),
+                };
+                let r = &x - &y * &q;
+                prop_assert_eq!(&y * &q + &r, x.clone());
+                prop_assert!(r.magnitude() < y.magnitude());
+                let contract = match mode {
+                    Floor => r.is_zero() || r.sign() == y.sign(),
+                    Ceiling => r.is_zero() || r.sign() != y.sign(),
+                    Truncate => r.is_zero() || r.sign() == x.sign(),
+                    _ => {
+                        // ties go to the even quotient
+                        let twice = r.magnitude() * 2_u32;
+                        twice < *y.magnitude() || (twice == *y.magnitude() && q.is_even())
+                    }
+                };
+                prop_assert!(contract, "{:?}: {} = {} * {} + {}", mode, x, y, q, r);
+            }
+        }
+    }
+
+    #[test]
+    fn test_divide_min_fixnum() {
+        assert_lisp("(floor (- (expt 2 55)) -1)", "36028797018963968");
+        assert_lisp("(cl-typep-number (truncate (- (expt 2 55)) -1) 'bignum)", "t");
+    }
+
+    #[test]
+    fn test_abs() {
+        assert_lisp("(abs -3)", "3");
+        assert_lisp("(abs 3)", "3");
+        assert_lisp("(abs -2.5)", "2.5");
+        assert_lisp("(abs -0.0)", "0.0");
+        assert_lisp("(abs (- (expt 2 70)))", "1180591620717411303424");
+        assert_lisp("(abs (- (expt 2 55)))", "36028797018963968");
+        assert_lisp("(cl-typep-number (abs (- (expt 2 55))) 'bignum)", "t");
+        // a value that is already positive is returned as is
+        assert_lisp("(let ((x 1.5)) (eq x (abs x)))", "t");
+        assert_lisp("(let ((x (expt 2 70))) (eq x (abs x)))", "t");
+        assert_lisp("(let ((x -1.5)) (eq x (abs x)))", "nil");
+    }
+
+    #[test]
+    fn test_logb() {
+        assert_lisp("(logb 1.0)", "0");
+        assert_lisp("(logb 8.0)", "3");
+        assert_lisp("(logb 10)", "3");
+        assert_lisp("(logb -8)", "3");
+        assert_lisp("(logb -0.5)", "-1");
+        assert_lisp("(logb (expt 2 70))", "70");
+        assert_lisp("(= (logb 0.0) (/ -1.0 0.0))", "t");
+        assert_lisp("(= (logb 0) (/ -1.0 0.0))", "t");
+        assert_lisp("(= (logb (/ 1.0 0.0)) (/ 1.0 0.0))", "t");
+        assert_lisp("(= (logb (/ -1.0 0.0)) (/ 1.0 0.0))", "t");
+        assert_lisp("(isnan (logb (/ 0.0 0.0)))", "t");
+    }
+
+    #[test]
+    fn test_rounding_range_error() {
+        for func in ["floor", "ceiling", "round", "truncate"] {
+            let nan = format!("(condition-case err ({func} (/ 0.0 0.0)) (error (car err)))");
+            assert_lisp(&nan, "range-error");
+            let inf = format!("(condition-case err ({func} (/ -1.0 0.0)) (error (car err)))");
+            assert_lisp(&inf, "range-error");
+            let inf = format!("(condition-case err ({func} (/ 1.0 0.0) 2) (error (car err)))");
+            assert_lisp(&inf, "range-error");
+            let zero = format!("(condition-case err ({func} 1.0 0.0) (error (car err)))");
+            assert_lisp(&zero, "range-error");
+            let nan = format!("(condition-case err ({func} 1 (/ 0.0 0.0)) (error (car err)))");
+            assert_lisp(&nan, "range-error");
+        }
+        let data = "(condition-case err (floor 1.0 0.0) (error (cdr err)))";
+        assert_lisp(&format!("(car {data})"), "\"floor\"");
+        assert_lisp(&format!("(= (car (cdr {data})) (/ 1.0 0.0))"), "t");
+        assert_lisp("(floor 1.0 (/ 1.0 0.0))", "0");
+        assert_lisp("(floor 7.5 2)", "3");
+    }
+
+    #[test]
+    fn test_cl_truncate() {
+        // both round toward zero, unlike floor
+        assert_lisp("(cl-truncate -2.5)", "-2");
+        assert_lisp("(truncate -2.5)", "-2");
+        assert_lisp("(floor -2.5)", "-3");
+        assert_lisp("(cl-truncate -7 2)", "-3");
+        assert_lisp("(cl-truncate -7.5 2)", "-3");
+        assert_lisp("(cl-truncate 7 -2.0)", "-3");
+        assert_lisp("(cl-truncate (- (expt 2 70)) 3)", "-393530540239137101141");
+        for x in ["-2.5", "-0.5", "-3.0", "-1e10"] {
+            assert_lisp(&format!("(= (cl-truncate {x}) (truncate {x}))"), "t");
+        }
+        assert_lisp("(condition-case err (cl-truncate 1 0) (error (car err)))", "arith-error");
+    }
+
+    #[test]
+    fn test_cl_rational() {
+        assert_lisp("(cl-rational 0.5)", "(1 . 2)");
+        assert_lisp("(cl-rational -0.75)", "(-3 . 4)");
+        assert_lisp("(cl-rational 0.0)", "(0 . 1)");
+        // 0.1 is not a tenth, but the nearest dyadic fraction to it
+        assert_lisp("(cl-rational 0.1)", "(3602879701896397 . 36028797018963968)");
+        assert_lisp("(cl-rational 1e20)", "(100000000000000000000 . 1)");
+        assert_lisp("(cl-rational 6)", "(6 . 1)");
+        assert_lisp("(cl-rational (expt 2 70))", "(1180591620717411303424 . 1)");
+        for x in ["(/ 0.0 0.0)", "(/ 1.0 0.0)", "(/ -1.0 0.0)"] {
+            let form = format!("(condition-case err (cl-rational {x}) (error (car err)))");
+            assert_lisp(&form, "range-error");
+        }
+    }
+}
@@ -1,16 +1,22 @@
 //! General purpose lisp functions
 use crate::{
+    character::{
+        MultibyteText, StringCodes, char_code, char_to_raw_byte, code_to_char, fold_char,
+        is_eight_bit, require_character, string_codes, string_from_codes, string_text,
+        texts_have_raw_bytes,
+    },
     core::{
         cons::Cons,
         env::{Env, sym},
         error::{Type, TypeError},
         gc::{Context, Rt, Rto},
         object::{
-            Function, Gc, HashTable, IntoObject, LispHashTable, LispString, LispVec, List,
-            ListType, NIL, Object, ObjectType, OptionalFlag, Symbol, WithLifetime,
+            Function, FunctionType, Gc, HashTable, IntoObject, LispHashTable, LispString, LispVec,
+            List, ListType, NIL, Number, Object, ObjectType, OptionalFlag, RawBytesText, Symbol,
+            WithLifetime,
         },
     },
-    data::aref,
+    data::{LispError, aref, string_char_at},
     library::filevercmp::filevercmp,
     rooted_iter,
 };
@@ -20,6 +26,7 @@ use fallible_iterator::FallibleIterator;
 use fallible_streaming_iterator::FallibleStreamingIterator;
 use rune_core::macros::{call, list, rebind, root};
 use rune_macros::{defun, elprop};
+use std::borrow::Cow;
 
 #[defun]
 fn identity(arg: Object) -> Object {
@@ -63,7 +70,10 @@ pub(crate) fn equal<'ob>(obj1: Object<'ob>, obj2: Object<'ob>) -> bool {
 #[defun]
 pub(crate) fn eql<'ob>(obj1: Object<'ob>, obj2: Object<'ob>) -> bool {
     match (obj1.untag(), obj2.untag()) {
+        // Like Emacs, floats are compared by bit pattern, so NaNs with the same
+        // sign and payload are eql but 0.0 and -0.0 are not.
         (ObjectType::Float(f1), ObjectType::Float(f2)) => f1.to_bits() == f2.to_bits(),
+        (ObjectType::BigInt(b1), ObjectType::BigInt(b2)) => b1 == b2,
         _ => obj1.ptr_eq(obj2),
     }
 }
@@ -114,15 +124,149 @@ pub(crate) fn prin1_to_string(object: Object, _noescape: Option<Object>) -> Stri
 }
 
 #[defun]
-fn string_to_multibyte(string: &LispString) -> &LispString {
-    // TODO: Handle the unibyte case
-    string
+fn string_to_multibyte<'ob>(string: Object<'ob>, cx: &'ob Context) -> Result<Object<'ob>> {
+    match string.untag() {
+        ObjectType::String(_) => Ok(string),
+        // non-ASCII bytes become eight-bit characters
+        ObjectType::ByteString(_) => {
+            let mut text = MultibyteText::default();
+            text.push_string(string)?;
+            Ok(cx.add(text))
+        }
+        _ => Err(TypeError::new(Type::String, string).into()),
+    }
+}
+
+/// The byte offset of the char position `pos` in `string`, where the end of
+/// the string is a valid position.
+fn char_to_byte_offset(string: &str, pos: usize) -> Option<usize> {
+    string.char_indices().map(|(idx, _)| idx).chain([string.len()]).nth(pos)
 }
 
 #[defun]
-fn string_search(needle: &str, haystack: &str, start_pos: Option<usize>) -> Option<usize> {
+fn string_search(needle: &str, haystack: &str, start_pos: Option<usize>) -> Result<Option<usize>> {
+    // positions are in chars, but the search is done on the UTF-8 bytes
     let start = start_pos.unwrap_or(0);
-    haystack[start..].find(needle).map(|x| x + start)
+    let Some(byte_start) = char_to_byte_offset(haystack, start) else {
+        bail!("Args out of range: {haystack:?}, {start}")
+    };
+    let rest = &haystack[byte_start..];
+    Ok(rest.find(needle).map(|idx| start + rest[..idx].chars().count()))
+}
+
+/// Count the non-overlapping occurrences of `needle` in `haystack`, from the
+/// char position `start`.
+#[defun]
+fn string_count(needle: &str, haystack: &str, start: Option<i64>, cx: &Context) -> Result<usize> {
+    // like `string-replace', an empty needle would match everywhere
+    if needle.is_empty() {
+        return Err(LispError::wrong_length(0, cx).into());
+    }
+    let start = start.unwrap_or(0);
+    let byte_start = usize::try_from(start).ok().and_then(|pos| char_to_byte_offset(haystack, pos));
+    let Some(byte_start) = byte_start else {
+        bail!("Args out of range: {haystack:?}, {start}")
+    };
+    Ok(haystack[byte_start..].matches(needle).count())
+}
+
+/// A copy of `string` that can be changed without affecting the original.
+fn fresh_string<'ob>(string: &LispString, cx: &'ob Context) -> &'ob LispString {
+    let text = string.inner().to_owned();
+    let copy: Gc<&LispString> = if string.has_raw_bytes() {
+        cx.add_as(RawBytesText(text))
+    } else {
+        cx.add_as(text)
+    };
+    copy.untag()
+}
+
+/// Replace every `from_string` in `in_string` with `to_string`. The result is
+/// always a new string, even if nothing was replaced. Like building it with
+/// `concat', it is only multibyte if one of the strings has non-ASCII
+/// multibyte text.
+#[defun]
+fn string_replace<'ob>(
+    from_string: Object,
+    to_string: Object,
+    in_string: Object<'ob>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    let text =
+        |string| string_text(string).ok_or_else(|| LispError::wrong_type(sym::STRINGP, string, cx));
+    let (from, to, text_in) = (text(from_string)?, text(to_string)?, text(in_string)?);
+    if from.is_empty() {
+        return Err(LispError::wrong_length(0, cx).into());
+    }
+    let strings = [from_string, to_string, in_string];
+    let multibyte = strings
+        .into_iter()
+        .any(|string| matches!(string.untag(), ObjectType::String(s) if !s.is_ascii()));
+    let raw_bytes = texts_have_raw_bytes(&strings)?;
+    // a single byte is always ASCII, and swapping it for another keeps every
+    // offset the same, so it can be done in place like `subst-char-in-string'
+    if let (true, &[from], &[to], ObjectType::String(lisp_string)) =
+        (multibyte, from.as_bytes(), to.as_bytes(), in_string.untag())
+    {
+        let copy = fresh_string(lisp_string, cx);
+        copy.replace_ascii(from, to);
+        return Ok(copy.into());
+    }
+    let replaced = text_in.replace(&*from, &to);
+    string_from_codes(replaced.chars().map(|c| char_code(c, raw_bytes)), multibyte, cx)
+}
+
+/// Replace `fromchar` with `tochar` in `string`. A non-nil `inplace` changes
+/// `string` itself. When both chars are ASCII the bytes are swapped in place,
+/// otherwise the text of the string is replaced.
+#[defun]
+fn subst_char_in_string<'ob>(
+    fromchar: Object,
+    tochar: Object,
+    string: Object<'ob>,
+    inplace: OptionalFlag,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    let from = require_character(fromchar, cx)?;
+    let to = require_character(tochar, cx)?;
+    let ascii = |code: i64| u8::try_from(code).ok().filter(u8::is_ascii);
+    if let (Some(from), Some(to), ObjectType::String(lisp_string)) =
+        (ascii(from), ascii(to), string.untag())
+    {
+        let target = if inplace.is_some() { lisp_string } else { fresh_string(lisp_string, cx) };
+        target.replace_ascii(from, to);
+        return Ok(target.into());
+    }
+    let Some(codes) = string_codes(string) else {
+        return Err(TypeError::new(Type::String, string).into());
+    };
+    let multibyte = codes.is_multibyte();
+    let fits = if multibyte {
+        code_to_char(to).is_some() || is_eight_bit(to)
+    } else {
+        to <= 0xFF
+    };
+    ensure!(fits, "Character {to:#x} can't be stored in this string");
+    let replaced = codes.map(|c| if c == from { to } else { c });
+    match string.untag() {
+        // the new char can have a different length in bytes, so the text is
+        // built again
+        ObjectType::String(lisp_string) if inplace.is_some() => {
+            let mut text = MultibyteText::default();
+            for code in replaced {
+                text.push_code(code)?;
+            }
+            lisp_string.set_text(text.as_str(), text.has_raw_bytes(), cx)?;
+            Ok(string)
+        }
+        ObjectType::ByteString(bytes) if inplace.is_some() => {
+            if let Ok(from) = u8::try_from(from) {
+                bytes.replace_byte(from, to as u8);
+            }
+            Ok(string)
+        }
+        _ => string_from_codes(replaced, multibyte, cx),
+    }
 }
 
 #[defun]
@@ -196,30 +340,28 @@ pub(crate) fn mapcan<'ob>(
 }
 
 #[defun]
-pub(crate) fn mapconcat(
+pub(crate) fn mapconcat<'ob>(
     function: &Rto<Function>,
     sequence: &Rto<Object>,
-    seperator: Option<&Rto<Gc<&LispString>>>,
+    seperator: Option<&Rto<Object>>,
     env: &mut Rt<Env>,
-    cx: &mut Context,
-) -> Result<String> {
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
     let mapped = rebind!(mapcar(function, sequence, env, cx)?);
-    let sep = match seperator {
-        Some(sep) => sep.bind(cx).untag(),
-        _ => "",
-    };
-    let mut string = String::new();
+    let sep = seperator.map(|sep| sep.bind(cx));
+    // raw bytes are kept apart from the chars that stand in for them, like
+    // in `concat'
+    let mut string = MultibyteText::default();
     let mut first = true;
     for element in mapped.as_list()? {
         if first {
             first = false;
-        } else {
-            string.push_str(sep);
+        } else if let Some(sep) = sep {
+            string.push_string(sep)?;
         }
-        let element: &str = element?.try_into()?;
-        string.push_str(element);
+        string.push_string(element?)?;
     }
-    Ok(string)
+    Ok(cx.add(string))
 }
 
 #[defun]
@@ -234,9 +376,17 @@ pub(crate) fn nreverse(seq: List) -> Result<Object> {
 }
 
 #[defun]
-pub(crate) fn reverse<'ob>(seq: List, cx: &'ob Context) -> Result<Object<'ob>> {
+pub(crate) fn reverse<'ob>(seq: Object<'ob>, cx: &'ob Context) -> Result<Object<'ob>> {
+    if let Some(codes) = string_codes(seq) {
+        let multibyte = codes.is_multibyte();
+        return string_from_codes(codes.rev(), multibyte, cx);
+    }
+    if let ObjectType::Vec(vec) = seq.untag() {
+        let reversed: Vec<_> = vec.iter().rev().map(|x| x.get()).collect();
+        return Ok(cx.add(reversed));
+    }
     let mut tail = NIL;
-    for elem in seq {
+    for elem in List::try_from(seq)? {
         tail = Cons::new(elem?, tail, cx).into();
     }
     Ok(tail)
@@ -282,18 +432,9 @@ pub(crate) fn append<'ob>(
     cx: &'ob Context,
 ) -> Result<Object<'ob>> {
     let mut list = Vec::new();
-    match append.untag() {
-        ObjectType::String(string) => {
-            for ch in string.chars() {
-                list.push((ch as i64).into());
-            }
-        }
-        ObjectType::ByteString(string) => {
-            for ch in string.iter() {
-                list.push((*ch as i64).into());
-            }
-        }
-        _ => join(&mut list, append.try_into()?)?,
+    match string_codes(append) {
+        Some(codes) => list.extend(codes.map(Object::from)),
+        None => join(&mut list, append.try_into()?)?,
     }
     for seq in sequences {
         join(&mut list, (*seq).try_into()?)?;
@@ -457,6 +598,13 @@ fn sort<'ob>(
     if vec.len() <= 1 {
         return Ok(seq.bind(cx).into());
     }
+    // Numbers are sorted with a total order, because NaN is unordered and
+    // would make the predicate inconsistent.
+    let numeric =
+        matches!(predicate.bind(cx).untag(), FunctionType::Symbol(sym::LESS_THAN | sym::VALUE_LT));
+    if let Some(sorted) = numeric.then(|| sort_numbers(&vec)).flatten() {
+        return Ok(slice_into_list(&sorted, None, cx));
+    }
     root!(vec, cx);
     let mut err = None;
     // TODO: Should we specialize some common predicates (<, >, string<, etc)?
@@ -483,6 +631,16 @@ fn sort<'ob>(
     }
 }
 
+/// Stably sort `objects` in ascending order if they are all numbers.
+fn sort_numbers<'ob>(objects: &[Object<'ob>]) -> Option<Vec<Object<'ob>>> {
+    let mut numbers = objects
+        .iter()
+        .map(|&obj| Some((Number::try_from(obj).ok()?.val(), obj)))
+        .collect::<Option<Vec<_>>>()?;
+    numbers.sort_by(|(a, _), (b, _)| a.cmp_total(b));
+    Some(numbers.into_iter().map(|(_, obj)| obj).collect())
+}
+
 #[defun]
 pub(crate) fn defvaralias<'ob>(
     new_alias: Symbol<'ob>,
@@ -500,7 +658,7 @@ pub(crate) fn featurep(_feature: Symbol, _subfeature: Option<Symbol>) {}
 #[defun]
 pub(crate) fn require<'ob>(
     feature: &Rto<Gc<Symbol>>,
-    filename: Option<&Rto<Gc<&LispString>>>,
+    filename: Option<&Rto<Object>>,
     noerror: OptionalFlag,
     env: &mut Rt<Env>,
     cx: &'ob mut Context,
@@ -511,10 +669,9 @@ pub(crate) fn require<'ob>(
         return Ok(feature.untag(cx));
     }
     let file = match filename {
-        Some(file) => file.untag(cx),
-        None => feature.untag(cx).get().name(),
+        Some(file) => file.bind(cx),
+        None => cx.add(feature.untag(cx).get().name()),
     };
-    let file = file.into_obj(cx);
     root!(file, cx);
     match crate::lread::load(file, noerror, None, cx, env) {
         Ok(_) => Ok(feature.untag(cx)),
@@ -523,27 +680,98 @@ pub(crate) fn require<'ob>(
 }
 
 #[defun]
-pub(crate) fn concat(sequences: &[Object]) -> Result<String> {
-    let mut concat = String::new();
+pub(crate) fn concat<'ob>(sequences: &[Object], cx: &'ob Context) -> Result<Object<'ob>> {
+    // The result is always a new string, even for a single string argument,
+    // so it is never `eq` to one of the sequences.
+    // Like Emacs, the result is only unibyte if none of the sequences contain
+    // multibyte characters. Otherwise the bytes of unibyte strings are
+    // upgraded to eight-bit characters. String literals are always read as
+    // multibyte here, so an ASCII multibyte string does not count, the same as
+    // a literal in Emacs.
+    if concat_is_multibyte(sequences)? {
+        let mut concat = MultibyteText::default();
+        for elt in sequences {
+            match elt.untag() {
+                ObjectType::String(_) | ObjectType::ByteString(_) => concat.push_string(*elt)?,
+                ObjectType::Cons(cons) => {
+                    for x in cons {
+                        concat.push_code(x?.try_into()?)?;
+                    }
+                }
+                ObjectType::Vec(vec) => {
+                    for x in vec.iter() {
+                        concat.push_code(x.get().try_into()?)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(cx.add(concat))
+    } else {
+        let mut concat: Vec<u8> = Vec::new();
+        for elt in sequences {
+            match elt.untag() {
+                ObjectType::String(string) => concat.extend_from_slice(string.as_bytes()),
+                ObjectType::ByteString(string) => concat.extend_from_slice(string),
+                ObjectType::Cons(cons) => {
+                    for x in cons {
+                        concat.push(i64::try_from(x?)? as u8);
+                    }
+                }
+                ObjectType::Vec(vec) => {
+                    for x in vec.iter() {
+                        concat.push(i64::try_from(x.get())? as u8);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(cx.add(concat))
+    }
+}
+
+/// Check if concatenating `sequences` requires a multibyte string, which is the
+/// case if any of them contains a non-ASCII char.
+fn concat_is_multibyte(sequences: &[Object]) -> Result<bool> {
+    let is_multibyte_char = |x: Object| -> Result<bool> {
+        let chr: i64 = x.try_into()?;
+        Ok(!(0..0x80).contains(&chr))
+    };
+    let mut multibyte = false;
     for elt in sequences {
         match elt.untag() {
-            ObjectType::String(string) => concat += string,
-            ObjectType::NIL => continue,
-            _ => bail!("Currently only concatenating strings are supported"),
+            ObjectType::String(string) => multibyte |= !string.is_ascii(),
+            ObjectType::ByteString(_) | ObjectType::NIL => {}
+            ObjectType::Cons(cons) => {
+                for x in cons {
+                    multibyte |= is_multibyte_char(x?)?;
+                }
+            }
+            ObjectType::Vec(vec) => {
+                for x in vec.iter() {
+                    multibyte |= is_multibyte_char(x.get())?;
+                }
+            }
+            obj => bail!(TypeError::new(Type::Sequence, obj)),
         }
     }
-    Ok(concat)
+    Ok(multibyte)
 }
 
 #[defun]
 pub(crate) fn vconcat<'ob>(sequences: &[Object], cx: &'ob Context) -> Result<Gc<&'ob LispVec>> {
+    // like `concat`, a single vector is still copied
     let mut concated: Vec<Object> = Vec::new();
     for elt in sequences {
         match elt.untag() {
-            // TODO: need to correctly handle unibyte strings (no unicode codepoints)
             ObjectType::String(string) => {
                 for chr in string.chars() {
-                    concated.push((chr as i64).into());
+                    concated.push(char_code(chr, string.has_raw_bytes()).into());
+                }
+            }
+            ObjectType::ByteString(string) => {
+                for byte in string.iter() {
+                    concated.push(i64::from(*byte).into());
                 }
             }
             ObjectType::Cons(cons) => {
@@ -565,11 +793,12 @@ pub(crate) fn vconcat<'ob>(sequences: &[Object], cx: &'ob Context) -> Result<Gc<
 
 #[defun]
 pub(crate) fn length(sequence: Object) -> Result<usize> {
+    if let Some(codes) = string_codes(sequence) {
+        return Ok(codes.count());
+    }
     let size = match sequence.untag() {
         ObjectType::Cons(x) => x.elements().len()?,
         ObjectType::Vec(x) => x.len(),
-        ObjectType::String(x) => x.len(),
-        ObjectType::ByteString(x) => x.len(),
         ObjectType::ByteFn(x) => x.len(),
         ObjectType::NIL => 0,
         obj => bail!(TypeError::new(Type::Sequence, obj)),
@@ -620,28 +849,17 @@ pub(crate) fn elt<'ob>(sequence: Object<'ob>, n: usize, cx: &'ob Context) -> Res
         ObjectType::NIL => Ok(NIL),
         ObjectType::Vec(x) => aref(x.into(), n, cx),
         ObjectType::Record(x) => aref(x.into(), n, cx),
-        ObjectType::String(x) => aref(x.into(), n, cx),
+        ObjectType::String(_) | ObjectType::ByteString(_) => {
+            Ok(string_char_at(sequence, n)?.into())
+        }
         ObjectType::ByteFn(x) => aref(x.into(), n, cx),
         other => Err(TypeError::new(Type::Sequence, other).into()),
     }
 }
 
 #[defun]
-pub(crate) fn string_equal<'ob>(s1: Object<'ob>, s2: Object<'ob>) -> Result<bool> {
-    let s1 = match s1.untag() {
-        ObjectType::String(x) => x.as_bytes(),
-        ObjectType::ByteString(x) => x.inner(),
-        ObjectType::Symbol(x) => x.get().as_bytes(),
-        _ => bail!(TypeError::new(Type::String, s1)),
-    };
-    let s2 = match s2.untag() {
-        ObjectType::String(x) => x.as_bytes(),
-        ObjectType::ByteString(x) => x.inner(),
-        ObjectType::Symbol(x) => (x.get()).as_bytes(),
-        _ => bail!(TypeError::new(Type::String, s2)),
-    };
-
-    Ok(s1 == s2)
+pub(crate) fn string_equal<'ob>(s1: Object<'ob>, s2: Object<'ob>, cx: &Context) -> Result<bool> {
+    Ok(string_or_symbol_codes(s1, cx)?.eq(string_or_symbol_codes(s2, cx)?))
 }
 
 #[defun]
@@ -689,14 +907,9 @@ pub(crate) fn compare_strings<'ob>(
     let s2 = string2.chars().skip(start2 as usize).take((end2 - start2) as usize);
 
     let mut leading = 1;
-    for (c1, c2) in s1.zip(s2) {
-        let (c1, c2) = if ignore_case.is_some() {
-            //TODO: use case-table to determine the uppercase of a character
-            (c1.to_uppercase().next().unwrap(), c2.to_uppercase().next().unwrap())
-        } else {
-            (c1, c2)
-        };
-
+    //TODO: use case-table to determine the uppercase of a character
+    let fold = |chr| if ignore_case.is_some() { fold_char(chr).next().unwrap() } else { chr };
+    for (c1, c2) in s1.map(fold).zip(s2.map(fold)) {
         match c1.cmp(&c2) {
             std::cmp::Ordering::Less => return Ok((-leading).into()),
             std::cmp::Ordering::Greater => return Ok(leading.into()),
@@ -753,13 +966,25 @@ pub(crate) fn levenshtein_distance<T: PartialEq, I: Iterator<Item = T>>(s1: I, s
 }
 
 #[defun]
-pub(crate) fn string_bytes(string: &str) -> usize {
-    string.len()
+pub(crate) fn string_bytes(string: Object) -> Result<usize> {
+    match string.untag() {
+        // Emacs uses 2 bytes for an eight-bit character, but the char that
+        // stands in for it is 4 bytes of UTF-8
+        ObjectType::String(x) if x.has_raw_bytes() => {
+            Ok(x.len() - 2 * x.chars().filter(|&c| char_to_raw_byte(c).is_some()).count())
+        }
+        ObjectType::String(x) => Ok(x.len()),
+        ObjectType::ByteString(x) => Ok(x.len()),
+        _ => Err(TypeError::new(Type::String, string).into()),
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum StringOrChar<'ob> {
     String(&'ob str),
+    /// A multibyte string that holds raw bytes
+    RawBytes(&'ob str),
+    ByteString(&'ob [u8]),
     Char(u64),
 }
 
@@ -768,7 +993,9 @@ impl<'ob> TryFrom<Object<'ob>> for StringOrChar<'ob> {
 
     fn try_from(obj: Object<'ob>) -> Result<Self, Self::Error> {
         match obj.untag() {
+            ObjectType::String(s) if s.has_raw_bytes() => Ok(Self::RawBytes(s)),
             ObjectType::String(s) => Ok(Self::String(s)),
+            ObjectType::ByteString(s) => Ok(Self::ByteString(s)),
             ObjectType::Int(c) if c >= 0 => Ok(Self::Char(c as u64)),
             _ => Err(TypeError::new(Type::StringOrChar, obj)),
         }
@@ -787,45 +1014,132 @@ impl From<char> for StringOrChar<'_> {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub(crate) struct StringOrSymbol<'ob>(&'ob str);
+/// The text of a string or the name of a symbol. Anything else signals
+/// `(wrong-type-argument stringp obj)`.
+pub(crate) fn string_or_symbol_name<'ob>(obj: Object<'ob>, cx: &Context) -> Result<Cow<'ob, str>> {
+    match obj.untag() {
+        ObjectType::Symbol(x) => Ok(Cow::Borrowed(x.get().name())),
+        _ => string_text(obj).ok_or_else(|| LispError::wrong_type(sym::STRINGP, obj, cx).into()),
+    }
+}
 
-impl<'ob> TryFrom<Object<'ob>> for StringOrSymbol<'ob> {
-    type Error = TypeError;
+/// The character codes of a string or the name of a symbol, which is what
+/// the string comparison functions accept. The raw bytes of a unibyte string
+/// are eight-bit characters, so they compare the same as in a multibyte
+/// string. Anything else signals `(wrong-type-argument stringp obj)` instead
+/// of being compared.
+fn string_or_symbol_codes<'ob>(
+    obj: Object<'ob>,
+    cx: &Context,
+) -> Result<Box<dyn Iterator<Item = i64> + 'ob>> {
+    match obj.untag() {
+        ObjectType::Symbol(x) => Ok(Box::new(x.get().name().chars().map(|c| char_code(c, false)))),
+        _ => Ok(Box::new(require_string_chars(obj, cx)?)),
+    }
+}
 
-    fn try_from(obj: Object<'ob>) -> Result<Self, Self::Error> {
-        match obj.untag() {
-            ObjectType::String(x) => Ok(Self(x)),
-            ObjectType::Symbol(x) => Ok(Self(x.get().name())),
-            _ => Err(TypeError::new(Type::String, obj)),
-        }
+#[defun]
+pub(crate) fn string_lessp(string1: Object, string2: Object, cx: &Context) -> Result<bool> {
+    let string1 = string_or_symbol_codes(string1, cx)?;
+    let string2 = string_or_symbol_codes(string2, cx)?;
+    // Compares by char, stopping at the first difference. If one string is a
+    // prefix of the other, the shorter one is less.
+    Ok(string1.lt(string2))
+}
+
+/// The character codes of a string, where the bytes of a unibyte string are
+/// eight-bit characters.
+fn string_chars(string: Object) -> Option<impl Iterator<Item = i64> + Clone + '_> {
+    string_codes(string).map(StringCodes::as_multibyte)
+}
+
+/// Like [`string_chars`], but signals an error if `string` is not a string.
+fn require_string_chars<'ob>(
+    string: Object<'ob>,
+    cx: &Context,
+) -> Result<impl Iterator<Item = i64> + Clone + 'ob> {
+    string_chars(string).ok_or_else(|| LispError::wrong_type(sym::STRINGP, string, cx).into())
+}
+
+/// Fold the case of the character `code` with [`fold_char`]. Codes that are
+/// not Unicode, like eight-bit characters, have no case.
+fn fold_code(code: i64) -> i64 {
+    match code_to_char(code) {
+        Some(chr) => fold_char(chr).map(|c| char_code(c, false)).next().unwrap(),
+        None => code,
     }
 }
 
+/// Whether `a` and `b` are the same chars, folding case if `ignore_case`.
+fn chars_match(
+    a: impl Iterator<Item = i64>,
+    b: impl Iterator<Item = i64>,
+    ignore_case: bool,
+) -> bool {
+    if ignore_case { a.map(fold_code).eq(b.map(fold_code)) } else { a.eq(b) }
+}
+
+// These compare chars rather than bytes, so part of a multibyte char never
+// matches, and an eight-bit char only matches the same raw byte.
+
 #[defun]
-pub(crate) fn string_lessp<'ob>(
-    string1: StringOrSymbol<'ob>,
-    string2: StringOrSymbol<'ob>,
+fn string_prefix_p(
+    prefix: Object,
+    string: Object,
+    ignore_case: OptionalFlag,
+    cx: &Context,
 ) -> Result<bool> {
-    for (c1, c2) in string1.0.chars().zip(string2.0.chars()) {
-        if c1 != c2 {
-            return Ok(c1 < c2);
-        }
-    }
-    Ok(string1.0.len() < string2.0.len())
+    let len = require_string_chars(prefix, cx)?.count();
+    // a string shorter than the prefix runs out before it
+    let head = require_string_chars(string, cx)?.take(len);
+    Ok(chars_match(require_string_chars(prefix, cx)?, head, ignore_case.is_some()))
 }
 
 #[defun]
-pub(crate) fn string_version_lessp<'ob>(
-    string1: StringOrSymbol<'ob>,
-    string2: StringOrSymbol<'ob>,
+fn string_suffix_p(
+    suffix: Object,
+    string: Object,
+    ignore_case: OptionalFlag,
+    cx: &Context,
 ) -> Result<bool> {
-    Ok(filevercmp(string1.0.as_bytes(), string2.0.as_bytes()) == std::cmp::Ordering::Less)
+    let len = require_string_chars(suffix, cx)?.count();
+    let total = require_string_chars(string, cx)?.count();
+    let Some(start) = total.checked_sub(len) else { return Ok(false) };
+    let tail = require_string_chars(string, cx)?.skip(start);
+    Ok(chars_match(require_string_chars(suffix, cx)?, tail, ignore_case.is_some()))
+}
+
+/// Compare two values of the same kind. Numbers compare by value, so NaN is
+/// neither less nor greater than anything, and strings and symbols compare
+/// like `string<`.
+#[defun(name = "value<")]
+pub(crate) fn value_lt(a: Object, b: Object, cx: &Context) -> Result<bool> {
+    if let (Ok(x), Ok(y)) = (Number::try_from(a), Number::try_from(b)) {
+        return Ok(x.val() < y.val());
+    }
+    if let (Some(x), Some(y)) = (string_chars(a), string_chars(b)) {
+        return Ok(x.lt(y));
+    }
+    match (a.untag(), b.untag()) {
+        (ObjectType::Symbol(x), ObjectType::Symbol(y)) => Ok(x.name().chars().lt(y.name().chars())),
+        _ => Err(LispError::type_mismatch(a, b, cx).into()),
+    }
+}
+
+#[defun]
+pub(crate) fn string_version_lessp(string1: Object, string2: Object, cx: &Context) -> Result<bool> {
+    let string1 = string_or_symbol_name(string1, cx)?;
+    let string2 = string_or_symbol_name(string2, cx)?;
+    Ok(filevercmp(string1.as_bytes(), string2.as_bytes()) == std::cmp::Ordering::Less)
 }
 
 #[defun]
-pub(crate) fn clear_string(string: &LispString) -> Result<Object> {
-    string.clear();
+pub(crate) fn clear_string<'ob>(string: Object, cx: &'ob Context) -> Result<Object<'ob>> {
+    match string.untag() {
+        ObjectType::String(string) => string.clear(),
+        ObjectType::ByteString(string) => string.clear(),
+        _ => return Err(LispError::wrong_type(sym::STRINGP, string, cx).into()),
+    }
     Ok(NIL)
 }
 
@@ -945,27 +1259,46 @@ fn copy_sequence<'ob>(arg: Object<'ob>, cx: &'ob Context) -> Result<Object<'ob>>
             }
             Ok(slice_into_list(&elements, tail, cx))
         }
-        ObjectType::String(x) => Ok(cx.add(x.to_owned())),
+        ObjectType::String(x) => Ok(fresh_string(x, cx).into()),
+        ObjectType::ByteString(x) => Ok(cx.add(x.to_vec())),
         ObjectType::NIL => Ok(NIL),
         _ => Err(TypeError::new(Type::Sequence, arg).into()),
     }
 }
 
 #[defun]
-fn substring(string: &str, from: Option<usize>, to: Option<usize>) -> Result<String> {
-    if from.unwrap_or_default() > string.len() || to.unwrap_or_default() > string.len() {
-        bail!("substring args out of range for {string} : {from:?} {to:?}");
-    }
-    let new_string = match (from, to) {
-        (None, None) => string,
-        (None, Some(t)) => &string[..t],
-        (Some(f), None) => &string[f..],
-        (Some(f), Some(t)) => {
-            let range = if f > t { t..f } else { f..t };
-            &string[range]
-        }
+fn substring<'ob>(
+    string: Object<'ob>,
+    from: Option<i64>,
+    to: Option<i64>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    // indexes are in chars and count from the end when negative. A unibyte
+    // string is sliced by bytes and stays unibyte.
+    let Some(codes) = string_codes(string) else {
+        return Err(TypeError::new(Type::String, string).into());
     };
-    Ok(new_string.to_owned())
+    let len = codes.clone().count() as i64;
+    let resolve = |idx: i64| if idx < 0 { idx + len } else { idx };
+    let start = from.map_or(0, resolve);
+    let end = to.map_or(len, resolve);
+    if !(0 <= start && start <= end && end <= len) {
+        bail!("Args out of range: {string}, {from:?}, {to:?}");
+    }
+    let (start, count) = (start as usize, (end - start) as usize);
+    let multibyte = codes.is_multibyte();
+    string_from_codes(codes.skip(start).take(count), multibyte, cx)
+}
+
+#[defun]
+fn substring_no_properties<'ob>(
+    string: Object<'ob>,
+    from: Option<i64>,
+    to: Option<i64>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    // TODO: strip the text properties once strings can have them
+    substring(string, from, to, cx)
 }
 
 defsym!(MD5);
@@ -1083,6 +1416,37 @@ mod test {
         assert_lisp("(nreverse '(1 2 3 4))", "(4 3 2 1)");
     }
 
+    #[test]
+    fn test_reverse_sequences() {
+        assert_lisp("(reverse '(1 2 3))", "(3 2 1)");
+        assert_lisp("(reverse [1 2 3])", "[3 2 1]");
+        assert_lisp("(reverse \"abc\")", "\"cba\"");
+        assert_lisp("(reverse \"\")", "\"\"");
+    }
+
+    #[test]
+    fn test_eight_bit_sequences() {
+        // a multibyte string holding an eight-bit char, and a unibyte string
+        let multi = r#"(concat "λ" (unibyte-string 255))"#;
+        let uni = "(unibyte-string 97 255)";
+        assert_lisp(&format!("(append {multi} nil)"), "(955 4194303)");
+        assert_lisp(&format!("(append {uni} nil)"), "(97 255)");
+        assert_lisp(&format!("(aref {multi} 1)"), "4194303");
+        assert_lisp(&format!("(aref {uni} 1)"), "255");
+        assert_lisp(&format!("(elt {multi} 1)"), "4194303");
+        assert_lisp(&format!("(elt {uni} 1)"), "255");
+        assert_lisp(&format!("(length {multi})"), "2");
+        assert_lisp(&format!("(length {uni})"), "2");
+        let reversed = r#"(concat (unibyte-string 255) "λ")"#;
+        assert_lisp(&format!("(equal (reverse {multi}) {reversed})"), "t");
+        assert_lisp(&format!("(equal (reverse {uni}) (unibyte-string 255 97))"), "t");
+        assert_lisp(&format!("(multibyte-string-p (reverse {uni}))"), "nil");
+        let raw = "(string-to-multibyte (unibyte-string 255))";
+        assert_lisp(&format!("(equal (substring {multi} 1) {raw})"), "t");
+        assert_lisp(&format!("(equal (substring {uni} 1) (unibyte-string 255))"), "t");
+        assert_lisp(&format!("(multibyte-string-p (substring {uni} 1))"), "nil");
+    }
+
     #[test]
     fn test_nconc() {
         assert_lisp("(nconc nil)", "nil");
@@ -1098,6 +1462,274 @@ mod test {
         assert_lisp("(append \"hello\")", "(104 101 108 108 111)");
     }
 
+    #[test]
+    fn test_string_lessp_by_char() {
+        assert_lisp("(string-lessp \"abc\" \"abd\")", "t");
+        assert_lisp("(string-lessp \"abd\" \"abc\")", "nil");
+        assert_lisp("(string-lessp \"abc\" \"abcd\")", "t");
+        assert_lisp("(string-lessp \"abcd\" \"abc\")", "nil");
+        assert_lisp("(string-lessp \"abc\" \"abc\")", "nil");
+        assert_lisp("(string-lessp \"\" \"a\")", "t");
+        assert_lisp("(string-lessp 'abc \"abd\")", "t");
+        // multibyte shared prefix
+        assert_lisp("(string-lessp \"λμ\" \"λμν\")", "t");
+        assert_lisp("(string-lessp \"λμν\" \"λμ\")", "nil");
+        assert_lisp("(string-lessp \"λμ\" \"λμ\")", "nil");
+        assert_lisp("(string-lessp \"λz\" \"λé\")", "t");
+    }
+
+    #[test]
+    fn test_concat() {
+        assert_lisp("(concat)", "\"\"");
+        assert_lisp("(concat \"foo\" nil \"bar\")", "\"foobar\"");
+        assert_lisp("(concat \"a\" '(98 99) [100])", "\"abcd\"");
+    }
+
+    #[test]
+    fn test_concat_copies() {
+        assert_lisp("(let ((s \"abc\")) (eq s (concat s)))", "nil");
+        assert_lisp("(let ((s \"abc\")) (equal s (concat s)))", "t");
+        assert_lisp("(let ((s (string 97))) (eq s (concat s)))", "nil");
+        assert_lisp("(let ((s \"\")) (eq s (concat s)))", "nil");
+        assert_lisp("(let ((s \"abc\")) (eq s (copy-sequence s)))", "nil");
+        assert_lisp("(let ((s (string 97))) (equal s (copy-sequence s)))", "t");
+        assert_lisp("(let ((v (vector 1 2))) (eq v (vconcat v)))", "nil");
+        // the copy can be modified without changing the original
+        assert_lisp(
+            "(let* ((v (vector 1 2)) (copy (vconcat v))) (aset copy 0 'x) (list v copy))",
+            "([1 2] [x 2])",
+        );
+    }
+
+    #[test]
+    fn test_concat_unibyte() {
+        // raw bytes are upgraded to eight-bit chars when mixed with multibyte
+        assert_lisp("(multibyte-string-p (concat (unibyte-string 255) \"λ\"))", "t");
+        assert_lisp("(length (concat (unibyte-string 255) \"λ\"))", "2");
+        assert_lisp("(multibyte-string-p (concat (unibyte-string 255) '(233)))", "t");
+        // but stay unibyte if everything is unibyte
+        assert_lisp(
+            "(multibyte-string-p (concat (unibyte-string 255) (unibyte-string 97)))",
+            "nil",
+        );
+        assert_lisp("(length (concat (unibyte-string 255) (unibyte-string 97) '(98)))", "3");
+        assert_lisp("(aref (concat (unibyte-string 255) (unibyte-string 97)) 0)", "255");
+        // ASCII-only strings do not make the result multibyte
+        assert_lisp("(multibyte-string-p (concat \"ab\" \"c\" '(100)))", "nil");
+        assert_lisp("(multibyte-string-p (concat (unibyte-string 255) \"x\"))", "nil");
+        assert_lisp("(concat \"ab\" (string ?c))", "\"abc\"");
+        assert_lisp("(string-bytes (concat \"é\" \"a\"))", "3");
+    }
+
+    #[test]
+    fn test_vconcat() {
+        assert_lisp("(vconcat)", "[]");
+        assert_lisp("(vconcat \"ab\" '(1 2))", "[97 98 1 2]");
+        assert_lisp("(vconcat [a] nil '(b) \"c\")", "[a b 99]");
+        assert_lisp("(vconcat (unibyte-string 255 97))", "[255 97]");
+    }
+
+    #[test]
+    fn test_elt() {
+        assert_lisp("(elt '(1 2 3) 1)", "2");
+        assert_lisp("(elt '(1 2 3) 5)", "nil");
+        assert_lisp("(elt [1 2 3] 2)", "3");
+        assert_lisp("(elt \"abc\" 0)", "97");
+        assert_lisp("(elt \"aλc\" 1)", "955");
+        assert_lisp("(elt \"aλc\" 2)", "99");
+        assert_lisp("(elt (concat [120]) 0)", "120");
+        assert_lisp("(condition-case nil (elt \"aλc\" 3) (error 'out-of-range))", "out-of-range");
+    }
+
+    #[test]
+    fn test_eql() {
+        assert_lisp("(eql 1 1)", "t");
+        assert_lisp("(eql 1 1.0)", "nil");
+        assert_lisp("(eql 1.5 1.5)", "t");
+        assert_lisp("(eql 0.0 -0.0)", "nil");
+        assert_lisp("(eql (expt 2 70) (expt 2 70))", "t");
+        assert_lisp("(eql (expt 2 70) (1+ (expt 2 70)))", "nil");
+        assert_lisp("(eql (expt 2 70) (float (expt 2 70)))", "nil");
+        assert_lisp("(let ((nan (/ 0.0 0.0))) (eql nan nan))", "t");
+        assert_lisp("(eql (/ 0.0 0.0) (- (/ 0.0 0.0)))", "nil");
+    }
+
+    #[test]
+    fn test_string_search() {
+        assert_lisp("(string-search \"b\" \"abcb\")", "1");
+        assert_lisp("(string-search \"b\" \"abcb\" 2)", "3");
+        assert_lisp("(string-search \"x\" \"abcb\")", "nil");
+        assert_lisp("(string-search \"\" \"abc\" 3)", "3");
+        assert_lisp("(string-search \"ν\" \"λμν\")", "2");
+        assert_lisp("(string-search \"ν\" \"λμν\" 1)", "2");
+        assert_lisp("(condition-case nil (string-search \"a\" \"abc\" 4) (error 'range))", "range");
+    }
+
+    #[test]
+    fn test_string_count() {
+        // matches don't overlap
+        assert_lisp("(string-count \"aa\" \"aaaa\")", "2");
+        assert_lisp("(string-count \"aa\" \"aaaaa\")", "2");
+        assert_lisp("(string-count \"aba\" \"ababa\")", "1");
+        assert_lisp("(string-count \"b\" \"abcb\")", "2");
+        assert_lisp("(string-count \"b\" \"abcb\" 2)", "1");
+        assert_lisp("(string-count \"x\" \"abcb\")", "0");
+        assert_lisp("(string-count \"λ\" \"λaλλ\" 1)", "2");
+        assert_lisp("(string-count \"a\" \"abc\" 3)", "0");
+        assert_lisp_signal("(string-count \"\" \"abc\")", "(wrong-length-argument 0)");
+        assert_lisp("(condition-case nil (string-count \"a\" \"abc\" 4) (error 'range))", "range");
+        assert_lisp("(condition-case nil (string-count \"a\" \"abc\" -1) (error 'range))", "range");
+    }
+
+    #[test]
+    fn test_string_replace() {
+        assert_lisp("(string-replace \".\" \"/\" \"a.b.λ.\")", "\"a/b/λ/\"");
+        assert_lisp("(string-replace \"ab\" \"x\" \"abcab\")", "\"xcx\"");
+        assert_lisp("(string-replace \"λ\" \"l\" \"λaλ\")", "\"lal\"");
+        assert_lisp("(string-replace \"a\" \"\" \"banana\")", "\"bnn\"");
+        // a new string is returned even if nothing matches
+        assert_lisp("(let ((s \"abc\")) (eq s (string-replace \"x\" \"y\" s)))", "nil");
+        assert_lisp("(let ((s \"abc\")) (equal s (string-replace \"x\" \"y\" s)))", "t");
+        // the fast path leaves the original alone
+        assert_lisp("(let ((s \"a.b\")) (string-replace \".\" \"-\" s) s)", "\"a.b\"");
+        assert_lisp_signal("(string-replace \"\" \"a\" \"abc\")", "(wrong-length-argument 0)");
+    }
+
+    #[test]
+    fn test_string_replace_unibyte() {
+        assert_lisp("(string-replace \"b\" \"x\" (string ?a ?b ?c))", "\"axc\"");
+        assert_lisp("(string-replace (string ?b) (string ?x ?y) \"abc\")", "\"axyc\"");
+        assert_lisp("(multibyte-string-p (string-replace \"b\" \"x\" (string ?a ?b)))", "nil");
+        assert_lisp("(multibyte-string-p (string-replace \"b\" \"λ\" (string ?a ?b)))", "t");
+        // raw bytes are kept
+        assert_lisp(
+            "(append (string-replace \"a\" \"b\" (unibyte-string 97 255)) nil)",
+            "(98 255)",
+        );
+        assert_lisp("(let ((s (string ?a))) (eq s (string-replace \"x\" \"y\" s)))", "nil");
+        assert_lisp_signal("(string-replace \"a\" \"b\" 1)", "(wrong-type-argument stringp 1)");
+    }
+
+    #[test]
+    fn test_string_replace_paths_agree() {
+        // the single char fast path has to match the general replacement
+        let strings = ["", "abc", "a.b.c", "..", "λ.μ.", "日本.語"];
+        let pairs = [('.', '/'), ('a', 'z'), ('.', '.'), ('x', 'y')];
+        for string in strings {
+            for (from, to) in pairs {
+                let general = string.replace(from, &to.to_string());
+                let fast = format!("(string-replace \"{from}\" \"{to}\" \"{string}\")");
+                assert_lisp(&fast, &format!("{general:?}"));
+                let subst = format!("(subst-char-in-string ?{from} ?{to} \"{string}\")");
+                assert_lisp(&subst, &format!("{general:?}"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_subst_char_in_string() {
+        assert_lisp("(subst-char-in-string ?. ?/ \"a.b\")", "\"a/b\"");
+        assert_lisp(
+            "(let ((s (copy-sequence \"a.b\"))) (subst-char-in-string ?. ?/ s t) s)",
+            "\"a/b\"",
+        );
+        assert_lisp(
+            "(let ((s (copy-sequence \"a.b\"))) (subst-char-in-string ?. ?/ s) s)",
+            "\"a.b\"",
+        );
+        assert_lisp("(subst-char-in-string ?λ ?x \"λaλ\")", "\"xax\"");
+        assert_lisp("(subst-char-in-string ?a ?λ \"aba\")", "\"λbλ\"");
+        let unibyte = "(subst-char-in-string 255 ?a (unibyte-string 255 98 255))";
+        assert_lisp(&format!("(append {unibyte} nil)"), "(97 98 97)");
+        // inplace also works when the new char is a different size
+        assert_lisp(
+            "(let ((s (copy-sequence \"λaλ\"))) (subst-char-in-string ?λ ?x s t) s)",
+            "\"xax\"",
+        );
+        assert_lisp(
+            "(let ((s (unibyte-string 255 98))) (subst-char-in-string 255 ?a s t) s)",
+            "\"ab\"",
+        );
+    }
+
+    #[test]
+    fn test_string_search_nul() {
+        assert_lisp("(string-search (string 0) (string 97 0 98 0))", "1");
+        assert_lisp("(string-search (string 0) (string 97 0 98 0) 2)", "3");
+        assert_lisp("(string-search (string 0 98) (string 97 0 98 0))", "1");
+        assert_lisp("(string-search (string 98) (string 0 0 98))", "2");
+        assert_lisp("(string-search (string 0) \"abc\")", "nil");
+        // the pieces `string-replace' is built from
+        assert_lisp(
+            "(let* ((s (string 97 0 98)) (i (string-search (string 0) s)))
+               (concat (substring s 0 i) \"-\" (substring s (1+ i))))",
+            "\"a-b\"",
+        );
+    }
+
+    #[test]
+    fn test_substring() {
+        assert_lisp("(substring \"hello\" 1 3)", "\"el\"");
+        assert_lisp("(substring \"hello\" 2)", "\"llo\"");
+        assert_lisp("(substring \"hello\" -3 -1)", "\"ll\"");
+        assert_lisp("(substring \"hello\" nil 2)", "\"he\"");
+        assert_lisp("(substring \"λμν\" 1 2)", "\"μ\"");
+        assert_lisp("(equal (substring (string 97 0 98) 1) (string 0 98))", "t");
+        assert_lisp("(condition-case nil (substring \"hello\" 3 1) (error 'range))", "range");
+        assert_lisp("(condition-case nil (substring \"hello\" 0 6) (error 'range))", "range");
+    }
+
+    #[test]
+    fn test_string_representations() {
+        // (multibyte-string-p string-bytes length) for strings made by each
+        // builtin. Eight-bit characters take 2 bytes in a multibyte string.
+        let raw = "(unibyte-string 97 255 98)";
+        let eight_bit = r#"(concat "λ" (unibyte-string 255))"#;
+        let cases = [
+            ("(string 97 98)", "(nil 2 2)"),
+            ("(string 97 955)", "(t 3 2)"),
+            ("(make-string 3 ?a)", "(nil 3 3)"),
+            ("(make-string 3 ?a t)", "(t 3 3)"),
+            ("(make-string 2 ?λ)", "(t 4 2)"),
+            ("RAW", "(nil 3 3)"),
+            ("(char-to-string ?a)", "(nil 1 1)"),
+            ("(char-to-string ?λ)", "(t 2 1)"),
+            ("(concat (string 97) RAW)", "(nil 4 4)"),
+            ("EIGHT-BIT", "(t 4 2)"),
+            ("(substring RAW 1)", "(nil 2 2)"),
+            ("(substring \"aλb\" 1)", "(t 3 2)"),
+            ("(substring EIGHT-BIT 1)", "(t 2 1)"),
+            ("(upcase RAW)", "(nil 3 3)"),
+            ("(downcase RAW)", "(nil 3 3)"),
+            ("(capitalize RAW)", "(nil 3 3)"),
+            ("(upcase \"aλ\")", "(t 3 2)"),
+            ("(string-to-multibyte RAW)", "(t 4 3)"),
+            ("(string-to-multibyte \"aλ\")", "(t 3 2)"),
+            ("(decode-coding-string RAW 'utf-8)", "(t 4 3)"),
+            ("(encode-coding-string \"aλ\" 'utf-8)", "(nil 3 3)"),
+        ];
+        for (string, expect) in cases {
+            let string = string.replace("RAW", raw).replace("EIGHT-BIT", eight_bit);
+            let form = format!(
+                "(let ((s {string})) (list (multibyte-string-p s) (string-bytes s) (length s)))"
+            );
+            assert_lisp(&form, expect);
+        }
+        assert_lisp(&format!("(equal (upcase {raw}) (unibyte-string 65 255 66))"), "t");
+    }
+
+    #[test]
+    fn test_substring_no_properties() {
+        assert_lisp("(substring-no-properties \"hello\")", "\"hello\"");
+        assert_lisp("(substring-no-properties \"hello\" 1 3)", "\"el\"");
+        assert_lisp("(substring-no-properties \"hello\" -3)", "\"llo\"");
+        assert_lisp("(substring-no-properties \"λμν\" nil -1)", "\"λμ\"");
+        assert_lisp(
+            "(condition-case nil (substring-no-properties \"hello\" 4 2) (error 'range))",
+            "range",
+        );
+    }
+
     #[test]
     fn test_assq() {
         assert_lisp("(assq 5 '((1 . 2) (3 . 4) (5 . 6)))", "(5 . 6)");
@@ -1108,6 +1740,22 @@ mod test {
     fn test_string_equal() {
         assert_lisp("(string-equal \"hello\" \"hello\")", "t");
         assert_lisp("(string-equal \"hello\" \"world\")", "nil");
+        assert_lisp("(string-equal 'foo \"foo\")", "t");
+        assert_lisp("(string-equal 'foo 'foo)", "t");
+        assert_lisp("(string-equal (unibyte-string 97) \"a\")", "t");
+        assert_lisp(
+            "(string-equal (unibyte-string 255) (string-to-multibyte (unibyte-string 255)))",
+            "t",
+        );
+        assert_lisp("(string-lessp (string ?a) \"b\")", "t");
+        assert_lisp("(string-lessp \"b\" (string ?a))", "nil");
+        assert_lisp("(string-lessp (make-string 2 ?a) 'ab)", "t");
+        assert_lisp("(string-version-lessp (make-string 1 ?a) \"a2\")", "t");
+        assert_lisp("(string-version-lessp (encode-coding-string \"a10\" 'utf-8) \"a9\")", "nil");
+        assert_lisp_signal("(string-equal 1 \"1\")", "(wrong-type-argument stringp 1)");
+        assert_lisp_signal("(string-equal \"a\" '(a))", "(wrong-type-argument stringp (a))");
+        assert_lisp_signal("(string-lessp 1.0 \"a\")", "(wrong-type-argument stringp 1.0)");
+        assert_lisp_signal("(string-version-lessp \"a\" 2)", "(wrong-type-argument stringp 2)");
     }
 
     #[test]
@@ -1117,6 +1765,72 @@ mod test {
         assert_lisp("(compare-strings \"hello\" 0 6 \"HELLO\" 0 6 t)", "t");
     }
 
+    #[test]
+    fn test_value_lt() {
+        assert_lisp("(value< 1 2)", "t");
+        assert_lisp("(value< 2 1)", "nil");
+        assert_lisp("(value< 1 1.5)", "t");
+        assert_lisp("(value< -1.5 (expt 2 70))", "t");
+        assert_lisp("(value< 1 1)", "nil");
+        // NaN is unordered
+        assert_lisp("(value< 1 (/ 0.0 0.0))", "nil");
+        assert_lisp("(value< (/ 0.0 0.0) 1)", "nil");
+        assert_lisp("(value< \"abc\" \"abd\")", "t");
+        assert_lisp("(value< \"ab\" \"abc\")", "t");
+        assert_lisp("(value< \"b\" \"abc\")", "nil");
+        assert_lisp("(value< (string 97) \"λ\")", "t");
+        assert_lisp("(value< 'apple 'banana)", "t");
+        assert_lisp("(value< 'banana 'apple)", "nil");
+        assert_lisp_signal("(value< 1 \"a\")", "(type-mismatch 1 \"a\")");
+        assert_lisp("(condition-case err (value< 'a \"b\") (error (car err)))", "type-mismatch");
+        assert_lisp("(condition-case err (value< '(1) '(2)) (error (car err)))", "type-mismatch");
+    }
+
+    #[test]
+    fn test_compare_strings_ignore_case() {
+        let compare = |s1, s2| format!("(compare-strings \"{s1}\" nil nil \"{s2}\" nil nil t)");
+        assert_lisp(&compare("ÀÉÎ", "àéî"), "t");
+        assert_lisp(&compare("ΣΑΣ", "σας"), "t");
+        assert_lisp(&compare("ǆ", "Ǆ"), "t");
+        // case is folded char by char, so multi-char expansions never match
+        assert_lisp(&compare("straße", "STRASSE"), "5");
+        assert_lisp(&compare("ß", "S"), "1");
+        assert_lisp(&compare("ß", "ß"), "t");
+        assert_lisp(&compare("ﬁx", "FIX"), "1");
+        assert_lisp(&compare("ﬁ", "ﬁ"), "t");
+    }
+
+    #[test]
+    fn test_string_prefix_suffix() {
+        assert_lisp("(string-prefix-p \"日本\" \"日本语\")", "t");
+        assert_lisp("(string-prefix-p \"日本语!\" \"日本语\")", "nil");
+        assert_lisp("(string-suffix-p \"语\" \"日本语\")", "t");
+        assert_lisp("(string-suffix-p \"本\" \"日本语\")", "nil");
+        assert_lisp("(string-suffix-p \"\" \"日本语\")", "t");
+        assert_lisp("(string-suffix-p \"日本语\" \"语\")", "nil");
+        assert_lisp("(string-suffix-p \"É\" \"café\" t)", "t");
+        assert_lisp("(string-prefix-p \"ÇA\" \"ça va\" t)", "t");
+        assert_lisp("(string-prefix-p \"ÇA\" \"ça va\")", "nil");
+        // IGNORE-CASE folds like `char-equal'
+        assert_lisp("(string-prefix-p \"ſΣ\" \"sςx\" t)", "t");
+        assert_lisp("(string-suffix-p \"ss\" \"straße\" t)", "nil");
+        // one char at a time, so a multi-char expansion never matches
+        assert_lisp("(string-prefix-p \"straße\" \"STRASSE 1\" t)", "nil");
+        assert_lisp("(string-prefix-p \"STRASS\" \"straße\" t)", "nil");
+        assert_lisp("(string-prefix-p \"ﬁ\" \"FIX\" t)", "nil");
+        assert_lisp("(string-prefix-p \"STRAẞE\" \"straße 1\" t)", "t");
+        // "±" is the bytes 194 177 and "€" is 226 130 172, so a byte check
+        // would match a unibyte string of just their last or first bytes
+        assert_lisp("(string-suffix-p (unibyte-string 177) \"±\")", "nil");
+        assert_lisp("(string-suffix-p (unibyte-string 130 172) \"a€\")", "nil");
+        assert_lisp("(string-prefix-p (unibyte-string 226) \"€a\")", "nil");
+        // but an eight-bit char does match its raw byte
+        let raw = "(concat \"λ\" (unibyte-string 177))";
+        assert_lisp(&format!("(string-suffix-p (unibyte-string 177) {raw})"), "t");
+        assert_lisp(&format!("(string-prefix-p \"λ\" {raw})"), "t");
+        assert_lisp_signal("(string-suffix-p 'a \"a\")", "(wrong-type-argument stringp a)");
+    }
+
     #[test]
     fn test_string_distance() {
         assert_lisp("(string-distance \"hello\" \"hello\")", "0");
@@ -1277,6 +1991,27 @@ mod test {
         assert_lisp("(safe-length 'foo)", "0");
     }
 
+    #[test]
+    fn test_sort_numbers() {
+        // -0.0 and 0.0 are equal, so the sort keeps them in order, and NaN
+        // sorts last
+        let sort = "(sort (list 1.0 (/ 0.0 0.0) -0.0 0.0 2) '{})";
+        let show = "(mapcar (lambda (x) (if (isnan x) 'nan x)) {})";
+        let sorted = |pred| show.replace("{}", &sort.replace("{}", pred));
+        assert_lisp(&sorted("value<"), "(-0.0 0.0 1.0 2 nan)");
+        assert_lisp(&sorted("<"), "(-0.0 0.0 1.0 2 nan)");
+        assert_lisp("(sort (list 0.0 -0.0 0 1) 'value<)", "(0.0 -0.0 0 1)");
+        assert_lisp("(sort (list 3 (expt 2 70) -1.5 2) '<)", "(-1.5 2 3 1180591620717411303424)");
+        // NaN is still unordered for the predicate itself
+        assert_lisp("(value< (/ 0.0 0.0) 1)", "nil");
+        assert_lisp("(value< 1 (/ 0.0 0.0))", "nil");
+        assert_lisp("(value< -0.0 0.0)", "nil");
+        assert_lisp("(value< 0.0 -0.0)", "nil");
+        // other predicates and non-numbers are sorted by calling the predicate
+        assert_lisp("(sort (list 1 3 2) (lambda (a b) (< a b)))", "(1 2 3)");
+        assert_lisp("(sort (list \"b\" \"a\") 'value<)", "(\"a\" \"b\")");
+    }
+
     #[test]
     fn test_sort() {
         assert_lisp("(sort nil '<)", "nil");
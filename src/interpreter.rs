@@ -672,7 +672,8 @@ impl Interpreter<'_, '_> {
                             } else {
                                 // TODO: Need to remove the anyhow branch once
                                 // full errors are implemented
-                                Cons::new(sym::ERROR, err_str, cx)
+                                let message = Cons::new1(err_str, cx);
+                                Cons::new(sym::ERROR, message, cx)
                             }
                         }
                         _ => unreachable!("Error type throw was not handled"),
@@ -843,6 +844,13 @@ pub(crate) fn assert_lisp(compare: &str, expect: &str) {
     assert_eq!(compare, expect);
 }
 
+/// Like [`assert_lisp`], but `expect` is the error that evaluating `form`
+/// signals, as a `condition-case` handler gets it.
+#[cfg(test)]
+pub(crate) fn assert_lisp_signal(form: &str, expect: &str) {
+    assert_lisp(&format!("(condition-case err {form} (error err))"), expect);
+}
+
 #[cfg(test)]
 mod test {
     use crate::core::{env::intern, gc::RootSet, object::IntoObject};
@@ -1104,6 +1112,20 @@ mod test {
             2,
             cx,
         );
+        // Errors without a signal still have their message as a list
+        check_interpreter(
+            r#"(condition-case e (format "%s") (error (cadr e)))"#,
+            "Not enough arguments for format string",
+            cx,
+        );
+        // so a handler can re-signal them
+        check_interpreter(
+            r#"(condition-case e
+                 (condition-case e (format "%s") (error (signal (car e) (cdr e))))
+               (error (cadr e)))"#,
+            "Not enough arguments for format string",
+            cx,
+        );
         check_error("(condition-case nil (if))", cx);
         check_error("(condition-case nil (if) nil)", cx);
         check_error("(condition-case nil (if) 5 (error 7))", cx);
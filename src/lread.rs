@@ -1,11 +1,11 @@
 //! Loading elisp from files and strings.
+use crate::character::string_text;
 use crate::core::cons::Cons;
 use crate::core::env::{Env, sym};
 use crate::core::error::{Type, TypeError};
 use crate::core::gc::{Context, Rt, Rto};
 use crate::core::object::{
-    Function, Gc, LispString, NIL, Object, ObjectType, OptionalFlag, Symbol, TRUE, TagType,
-    WithLifetime,
+    Function, NIL, Object, ObjectType, OptionalFlag, Symbol, TRUE, TagType, WithLifetime,
 };
 use crate::reader;
 use crate::{interpreter, rooted_iter};
@@ -136,17 +136,14 @@ fn find_file_in_load_path(file: &str, cx: &Context, env: &Rt<Env>) -> Result<Pat
     let paths = load_path.bind(cx).as_list().context("`load-path' was not a list")?;
     let mut final_file = None;
     for path in paths {
-        match path?.untag() {
-            ObjectType::String(path) => {
-                if let Some(x) = file_in_path(file, path) {
-                    final_file = Some(x);
-                    break;
-                }
-            }
-            x => {
-                return Err(TypeError::new(Type::String, x))
-                    .context("Found non-string in `load-path'");
-            }
+        let path = path?;
+        let Some(dir) = string_text(path) else {
+            return Err(TypeError::new(Type::String, path))
+                .context("Found non-string in `load-path'");
+        };
+        if let Some(x) = file_in_path(file, &dir) {
+            final_file = Some(x);
+            break;
         }
     }
     final_file.ok_or_else(|| anyhow!("Unable to find file `{file}' in load-path"))
@@ -154,7 +151,7 @@ fn find_file_in_load_path(file: &str, cx: &Context, env: &Rt<Env>) -> Result<Pat
 
 #[defun]
 pub(crate) fn load(
-    file: &Rto<Gc<&LispString>>,
+    file: &Rto<Object>,
     noerror: OptionalFlag,
     nomessage: OptionalFlag,
     cx: &mut Context,
@@ -162,11 +159,13 @@ pub(crate) fn load(
 ) -> Result<bool> {
     let noerror = noerror.is_some();
     let nomessage = nomessage.is_some();
-    let file: &str = file.untag(cx);
-    let final_file = if Path::new(file).exists() {
-        PathBuf::from(file)
+    let Some(file) = string_text(file.bind(cx)) else {
+        bail!(TypeError::new(Type::String, file.bind(cx)));
+    };
+    let final_file = if Path::new(&*file).exists() {
+        PathBuf::from(&*file)
     } else {
-        match find_file_in_load_path(file, cx, env) {
+        match find_file_in_load_path(&file, cx, env) {
             Ok(x) => x,
             Err(e) => {
                 return if noerror { Ok(false) } else { Err(e) };
@@ -174,7 +173,7 @@ pub(crate) fn load(
         }
     };
 
-    let filename = String::from(file);
+    let filename = file.into_owned();
     if !nomessage {
         println!("Loading {filename}...");
     }
@@ -221,14 +220,16 @@ pub(crate) fn intern_soft(string: Object, obarray: OptionalFlag) -> Result<Symbo
                 Ok(sym::NIL)
             }
         }
-        ObjectType::String(string) => {
+        _ => {
+            let Some(string) = string_text(string) else {
+                bail!(TypeError::new(Type::String, string));
+            };
             let map = crate::core::env::INTERNED_SYMBOLS.lock().unwrap();
-            match map.get(string) {
+            match map.get(&*string) {
                 Some(sym) => Ok(unsafe { sym.with_lifetime() }),
                 None => Ok(sym::NIL),
             }
         }
-        x => Err(TypeError::new(Type::String, x).into()),
     }
 }
 
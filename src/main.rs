@@ -12,6 +12,7 @@ mod bytecode;
 mod casefiddle;
 mod character;
 mod chartab;
+mod coding;
 mod data;
 mod dired;
 mod editfns;
@@ -37,7 +38,7 @@ mod timefns;
 use crate::core::{
     env::{Env, intern, sym},
     gc::{Context, RootSet, Rt},
-    object::{Gc, LispString, NIL},
+    object::NIL,
 };
 use crate::eval::EvalError;
 use clap::Parser;
@@ -133,7 +134,7 @@ fn repl(env: &mut Rt<Env>, cx: &mut Context) {
 }
 
 fn load(file: &str, cx: &mut Context, env: &mut Rt<Env>) -> Result<(), ()> {
-    let file: Gc<&LispString> = cx.add_as(file);
+    let file = cx.add(file);
     root!(file, cx);
     match crate::lread::load(file, None, None, cx, env) {
         Ok(val) => {
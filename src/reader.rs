@@ -1,15 +1,15 @@
 //! Lisp reader that reads an object from a string.
+use crate::arith::parse_int_in_radix;
 use crate::core::{
     env::{intern, sym},
     gc::Context,
     object::{Object, Symbol},
 };
 use crate::fns;
-use num_bigint::BigInt;
 use rune_core::macros::list;
+use std::fmt::Display;
 use std::str;
 use std::{fmt, iter::Peekable, str::CharIndices};
-use std::{fmt::Display, str::FromStr};
 
 type Result<T> = std::result::Result<T, Error>;
 
@@ -310,10 +310,9 @@ fn intern_symbol<'ob>(symbol: &str, cx: &'ob Context) -> Symbol<'ob> {
 /// Parse a symbol from a string. This will either by a true symbol or a number
 /// literal.
 fn parse_symbol<'a>(slice: &str, cx: &'a Context) -> Object<'a> {
-    if let Ok(num) = slice.parse::<i64>() {
-        return cx.add(num);
-    }
-    if let Ok(num) = BigInt::from_str(slice) {
+    // Most symbols have no digits, so skip building an error for them
+    let int = slice.bytes().any(|b| b.is_ascii_digit()).then(|| parse_int_in_radix(slice, 10));
+    if let Some(Ok(num)) = int {
         return cx.add(num);
     }
     match slice.parse::<f64>() {
@@ -446,8 +445,8 @@ impl<'a, 'ob> Reader<'a, 'ob> {
         }
 
         match self.tokens.next() {
-            Some(Ok(Token::Ident(ident))) => match usize::from_str_radix(ident, radix.into()) {
-                Ok(x) => Ok(self.cx.add(x as i64)),
+            Some(Ok(Token::Ident(ident))) => match parse_int_in_radix(ident, radix.into()) {
+                Ok(x) => Ok(self.cx.add(x)),
                 Err(_) => Err(Error::ParseInt(radix, pos)),
             },
             _ => Err(Error::ParseInt(radix, pos)),
@@ -579,6 +578,7 @@ mod test {
         check_reader!(0xdead_beef_i64, "#xDeAdBeEf", cx);
         check_reader!(171, "#12r0123", cx);
         check_reader!(49360, "#36r1234", cx);
+        check_reader!(-255, "#x-ff", cx);
         assert_error("#37r1234", Error::ParseInt(37, 0), cx);
         assert_error("#257r1234", Error::UnknownMacroCharacter('7', 0), cx);
         assert_error("#123456r1234", Error::UnknownMacroCharacter('4', 0), cx);
@@ -594,6 +594,9 @@ mod test {
         check_reader!(225, "?á", cx);
         check_reader!(97, "?a?a", cx);
         check_reader!(97, "?a#'foo ?a", cx);
+        // plane 16 private use chars are ordinary chars
+        check_reader!(0x10_FFFF, "?\u{10FFFF}", cx);
+        check_reader!(0x10_FFC8, "?\\u10FFC8", cx);
         assert_error("?aa", Error::UnexpectedChar('a', 2), cx);
         assert_error("?", Error::MissingQuotedItem(0), cx);
     }
@@ -637,6 +640,7 @@ mod test {
 baz""#,
             cx
         );
+        check_reader!("a\u{10FF80}", "\"a\u{10FF80}\"", cx);
     }
 
     #[test]
@@ -1,14 +1,19 @@
 //! Search utilities.
-use crate::core::{
-    cons::Cons,
-    env::Env,
-    gc::{Context, Rt},
-    object::{List, NIL, Object, ObjectType, OptionalFlag},
+use crate::{
+    buffer::case_fold_search,
+    core::{
+        cons::Cons,
+        env::Env,
+        gc::{Context, Rt},
+        object::{List, NIL, Object, ObjectType, OptionalFlag, RawBytesText},
+    },
 };
 use anyhow::{Result, bail, ensure};
 use fallible_iterator::FallibleIterator;
 use fancy_regex::Regex;
+use rune_core::hashmap::HashMap;
 use rune_macros::defun;
+use std::sync::{LazyLock, Mutex};
 
 #[defun]
 fn string_match<'ob>(
@@ -20,7 +25,11 @@ fn string_match<'ob>(
     cx: &'ob Context,
 ) -> Result<Object<'ob>> {
     // TODO: implement inhibit-modify
-    let re = Regex::new(&lisp_regex_to_rust(regexp))?;
+    let mut regexp = lisp_regex_to_rust(regexp);
+    if case_fold_search(env) {
+        regexp.insert_str(0, "(?i)");
+    }
+    let re = Regex::new(&regexp)?;
 
     let start = start.unwrap_or(0) as usize;
     if let Some(matches) = re.captures_iter(&string[start..]).next() {
@@ -123,6 +132,109 @@ fn lisp_regex_to_rust(regexp: &str) -> String {
     norm_regex
 }
 
+/// The regexp `string-trim` uses when none is given.
+const TRIM_DEFAULT: &str = "[ \t\n\r]+";
+
+static TRIM_START_DEFAULT: LazyLock<Regex> =
+    LazyLock::new(|| compile_trim_regex(TRIM_DEFAULT, true, false).unwrap());
+static TRIM_END_DEFAULT: LazyLock<Regex> =
+    LazyLock::new(|| compile_trim_regex(TRIM_DEFAULT, false, false).unwrap());
+
+/// Custom trim regexps that have already been compiled, keyed by the Lisp
+/// regexp, whether it is anchored at the start and whether it ignores case.
+/// Lisp regexps are always strings, so this stands in for passing a compiled
+/// one. Callers tend to use the same few regexps over and over, so this is
+/// cleared instead of evicting entries.
+static TRIM_CACHE: LazyLock<Mutex<HashMap<(String, bool, bool), Regex>>> =
+    LazyLock::new(Mutex::default);
+const TRIM_CACHE_SIZE: usize = 64;
+
+fn compile_trim_regex(regexp: &str, start: bool, fold: bool) -> Result<Regex> {
+    let regexp = lisp_regex_to_rust(regexp);
+    let mut anchored = if start { format!("\\A(?:{regexp})") } else { format!("(?:{regexp})\\z") };
+    if fold {
+        anchored.insert_str(0, "(?i)");
+    }
+    Ok(Regex::new(&anchored)?)
+}
+
+fn trim_regex(regexp: Option<&str>, start: bool, env: &Rt<Env>) -> Result<Regex> {
+    let Some(regexp) = regexp else {
+        let default = if start { &TRIM_START_DEFAULT } else { &TRIM_END_DEFAULT };
+        return Ok(Regex::clone(default));
+    };
+    // like `string-match', a custom regexp follows `case-fold-search'
+    let fold = case_fold_search(env);
+    let key = (regexp.to_owned(), start, fold);
+    let mut cache = TRIM_CACHE.lock().unwrap();
+    if let Some(re) = cache.get(&key) {
+        return Ok(re.clone());
+    }
+    let re = compile_trim_regex(regexp, start, fold)?;
+    if cache.len() >= TRIM_CACHE_SIZE {
+        cache.clear();
+    }
+    cache.insert(key, re.clone());
+    Ok(re)
+}
+
+/// Trim the text matched by `left` and `right` from the ends of `string`. The
+/// result keeps the raw bytes of a string that has them.
+fn trim<'ob>(
+    string: Object,
+    left: Option<Regex>,
+    right: Option<Regex>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    let text: &str = string.try_into()?;
+    let end = match right {
+        Some(re) => re.find(text)?.map_or(text.len(), |m| m.start()),
+        None => text.len(),
+    };
+    let start = match left {
+        Some(re) => re.find(&text[..end])?.map_or(0, |m| m.end()),
+        None => 0,
+    };
+    let trimmed = &text[start..end];
+    match string.untag() {
+        ObjectType::String(s) if s.has_raw_bytes() => Ok(cx.add(RawBytesText(trimmed.to_owned()))),
+        _ => Ok(cx.add(trimmed)),
+    }
+}
+
+#[defun]
+fn string_trim_left<'ob>(
+    string: Object,
+    regexp: Option<&str>,
+    env: &Rt<Env>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    trim(string, Some(trim_regex(regexp, true, env)?), None, cx)
+}
+
+#[defun]
+fn string_trim_right<'ob>(
+    string: Object,
+    regexp: Option<&str>,
+    env: &Rt<Env>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    trim(string, None, Some(trim_regex(regexp, false, env)?), cx)
+}
+
+#[defun]
+fn string_trim<'ob>(
+    string: Object,
+    trim_left: Option<&str>,
+    trim_right: Option<&str>,
+    env: &Rt<Env>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    let left = trim_regex(trim_left, true, env)?;
+    let right = trim_regex(trim_right, false, env)?;
+    trim(string, Some(left), Some(right), cx)
+}
+
 #[defun]
 fn match_data<'ob>(
     integer: OptionalFlag,
@@ -175,6 +287,7 @@ fn match_data__translate(n: i64, env: &Rt<Env>, cx: &Context) -> Result<()> {
 #[cfg(test)]
 mod test {
     use crate::core::gc::RootSet;
+    use crate::interpreter::assert_lisp;
     use rune_core::macros::root;
 
     use super::*;
@@ -191,6 +304,40 @@ mod test {
         assert_eq!(lisp_regex_to_rust("[[:word:]_]"), "[a-zA-Z_]");
     }
 
+    #[test]
+    fn test_string_match_case_fold() {
+        assert_lisp("(string-match \"B\" \"abc\")", "1");
+        assert_lisp("(let ((case-fold-search nil)) (string-match \"B\" \"abc\"))", "nil");
+    }
+
+    #[test]
+    fn test_string_trim() {
+        assert_lisp("(string-trim \"  foo bar \n\t\")", "\"foo bar\"");
+        assert_lisp("(string-trim \"foo\")", "\"foo\"");
+        assert_lisp("(string-trim \" \r\n \")", "\"\"");
+        assert_lisp("(string-trim-left \"  foo  \")", "\"foo  \"");
+        assert_lisp("(string-trim-right \"  foo  \")", "\"  foo\"");
+    }
+
+    #[test]
+    fn test_string_trim_custom() {
+        // custom regexps only remove text at the ends of the string
+        assert_lisp("(string-trim \"--foo-bar==\" \"-+\" \"=+\")", "\"foo-bar\"");
+        assert_lisp("(string-trim \"x-x\" \"-\" \"-\")", "\"x-x\"");
+        assert_lisp("(string-trim-left \"ababc\" \"\\\\(ab\\\\)+\")", "\"c\"");
+        // the default is still used for a side without a regexp
+        assert_lisp("(string-trim \" xfoox \" \"x\")", "\" xfoox\"");
+        assert_lisp("(string-trim \"xfoo \" \"x\")", "\"foo\"");
+        // the same regexp works on both sides once cached
+        assert_lisp("(string-trim \"xfoox\" \"x\" \"x\")", "\"foo\"");
+        // custom regexps follow `case-fold-search' like `string-match'
+        assert_lisp("(string-trim \"XfooX\" \"x\" \"x\")", "\"foo\"");
+        assert_lisp(
+            "(let ((case-fold-search nil)) (string-trim \"XfooX\" \"x\" \"x\"))",
+            "\"XfooX\"",
+        );
+    }
+
     #[test]
     fn test_replace_match() {
         let roots = &RootSet::default();